@@ -10,10 +10,82 @@ impl BlockId {
     pub const STONE: BlockId = BlockId(1);
     pub const DIRT: BlockId = BlockId(2);
     pub const GRASS: BlockId = BlockId(3);
+    pub const LOG: BlockId = BlockId(4);
+    pub const LEAVES: BlockId = BlockId(5);
+    pub const FURNACE: BlockId = BlockId(6);
+    pub const MACHINE_CASING: BlockId = BlockId(7);
+    pub const INPUT_HATCH: BlockId = BlockId(8);
+    pub const OUTPUT_HATCH: BlockId = BlockId(9);
+    pub const ENERGY_PORT: BlockId = BlockId(10);
+    pub const BLAST_FURNACE: BlockId = BlockId(11);
 }
 
-/// Block Direction
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Block State (block id plus property bits)
+///
+/// Layout: bits 0..16 block id, 16..19 facing, 19 powered, 20..28 variant.
+/// The layout is fixed so chunks and region files never need the registry
+/// to read a state. Chunks only store the states they use in their palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlockState(pub u32);
+
+impl BlockState {
+    pub const AIR: BlockState = BlockState(0);
+
+    const FACING_SHIFT: u32 = 16;
+    const FACING_MASK: u32 = 0b111;
+    const POWERED_SHIFT: u32 = 19;
+    const VARIANT_SHIFT: u32 = 20;
+    const VARIANT_MASK: u32 = 0xFF;
+
+    pub fn block(self) -> BlockId {
+        BlockId(self.0 as u16)
+    }
+
+    /// Raw Facing Bits (use `BlockRegistry::facing` to respect the block's properties)
+    pub fn facing_bits(self) -> Direction {
+        let index = (self.0 >> Self::FACING_SHIFT) & Self::FACING_MASK;
+        Direction::from_index(index as u8).unwrap_or(Direction::North)
+    }
+
+    pub fn powered_bit(self) -> bool {
+        self.0 & (1 << Self::POWERED_SHIFT) != 0
+    }
+
+    pub fn variant_bits(self) -> u8 {
+        ((self.0 >> Self::VARIANT_SHIFT) & Self::VARIANT_MASK) as u8
+    }
+
+    fn with_bits(self, shift: u32, mask: u32, value: u32) -> BlockState {
+        BlockState((self.0 & !(mask << shift)) | ((value & mask) << shift))
+    }
+}
+
+impl From<BlockId> for BlockState {
+    /// Default State (all properties zero)
+    fn from(block: BlockId) -> Self {
+        BlockState(block.0 as u32)
+    }
+}
+
+/// Property A Block Can Declare
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateProperty {
+    Facing,
+    Powered,
+    /// Number of variants (at most 256)
+    Variant(u16),
+}
+
+/// Property Values Of A State
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StateValues {
+    pub facing: Option<Direction>,
+    pub powered: Option<bool>,
+    pub variant: Option<u8>,
+}
+
+/// Block Direction (North = +Z, East = +X)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction {
     North,
     South,
@@ -23,6 +95,60 @@ pub enum Direction {
     Down,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    /// Unit Step Toward This Direction
+    pub fn offset(self) -> IVec3 {
+        match self {
+            Direction::North => IVec3::Z,
+            Direction::South => IVec3::NEG_Z,
+            Direction::East => IVec3::X,
+            Direction::West => IVec3::NEG_X,
+            Direction::Up => IVec3::Y,
+            Direction::Down => IVec3::NEG_Y,
+        }
+    }
+
+    /// Position In `ALL` (used for compact storage)
+    pub fn index(self) -> u8 {
+        Self::ALL.iter().position(|direction| *direction == self).unwrap() as u8
+    }
+
+    pub fn from_index(index: u8) -> Option<Direction> {
+        Self::ALL.get(index as usize).copied()
+    }
+
+    /// Dominant Horizontal Direction Of A Look Vector
+    pub fn from_look(look: Vec3) -> Direction {
+        if look.x.abs() > look.z.abs() {
+            if look.x > 0.0 { Direction::East } else { Direction::West }
+        } else if look.z > 0.0 {
+            Direction::North
+        } else {
+            Direction::South
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+}
+
 /// Efficient Tool
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ToolType {
@@ -46,6 +172,10 @@ pub struct BlockProperties {
     pub can_contain_fluid: bool,
     pub is_multiblock_part: bool,
     pub is_multiblock_controller: bool,
+    /// State properties, in no particular order
+    pub state_properties: Vec<StateProperty>,
+    /// Lowercase group names, e.g. for multiblock matchers
+    pub tags: Vec<String>,
     // Textures will be added later
     pub debug_color: Color,
 }
@@ -63,13 +193,15 @@ impl Default for BlockProperties {
             can_contain_fluid: false,
             is_multiblock_part: false,
             is_multiblock_controller: false,
+            state_properties: Vec::new(),
+            tags: Vec::new(),
             debug_color: Color::WHITE,
         }
     }
 }
 
 /// Global Registry for Blocks
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone)]
 pub struct BlockRegistry {
     blocks: HashMap<BlockId, BlockProperties>,
 }
@@ -91,12 +223,81 @@ impl BlockRegistry {
         self.blocks.get(&id)
     }
 
+    /// All Registered Blocks, In No Particular Order
+    pub fn iter(&self) -> impl Iterator<Item = &BlockProperties> {
+        self.blocks.values()
+    }
+
     pub fn get_or_air(&self, id: BlockId) -> &BlockProperties {
         self.blocks.get(&id).unwrap_or_else(|| {
             self.blocks.get(&BlockId::AIR).unwrap()
         })
     }
 
+    pub fn has_tag(&self, block: BlockId, tag: &str) -> bool {
+        self.get(block).is_some_and(|props| props.tags.iter().any(|other| other == tag))
+    }
+
+    fn has_property(&self, block: BlockId, wanted: impl Fn(&StateProperty) -> bool) -> bool {
+        self.get(block).is_some_and(|props| props.state_properties.iter().any(wanted))
+    }
+
+    fn variant_count(&self, block: BlockId) -> Option<u16> {
+        self.get(block)?.state_properties.iter().find_map(|property| match property {
+            StateProperty::Variant(count) => Some(*count),
+            _ => None,
+        })
+    }
+
+    /// Values Of The Properties A State's Block Declares
+    pub fn state_values(&self, state: BlockState) -> StateValues {
+        let block = state.block();
+        StateValues {
+            facing: self.facing(state),
+            powered: self
+                .has_property(block, |p| *p == StateProperty::Powered)
+                .then(|| state.powered_bit()),
+            variant: self.variant_count(block).map(|_| state.variant_bits()),
+        }
+    }
+
+    /// State For A Block With The Given Values (None if the block lacks a property)
+    pub fn state_with(&self, block: BlockId, values: StateValues) -> Option<BlockState> {
+        let mut state = BlockState::from(block);
+        if let Some(facing) = values.facing {
+            if !self.has_property(block, |p| *p == StateProperty::Facing) {
+                return None;
+            }
+            state = state.with_bits(BlockState::FACING_SHIFT, BlockState::FACING_MASK, facing.index() as u32);
+        }
+        if let Some(powered) = values.powered {
+            if !self.has_property(block, |p| *p == StateProperty::Powered) {
+                return None;
+            }
+            state = state.with_bits(BlockState::POWERED_SHIFT, 1, powered as u32);
+        }
+        if let Some(variant) = values.variant {
+            if self.variant_count(block).is_none_or(|count| variant as u16 >= count) {
+                return None;
+            }
+            state = state.with_bits(BlockState::VARIANT_SHIFT, BlockState::VARIANT_MASK, variant as u32);
+        }
+        Some(state)
+    }
+
+    pub fn facing(&self, state: BlockState) -> Option<Direction> {
+        self.has_property(state.block(), |p| *p == StateProperty::Facing)
+            .then(|| state.facing_bits())
+    }
+
+    /// Rotate A State, Leaving Blocks Without Facing Untouched
+    pub fn with_facing(&self, state: BlockState, facing: Direction) -> BlockState {
+        if self.facing(state).is_none() {
+            return state;
+        }
+        state.with_bits(BlockState::FACING_SHIFT, BlockState::FACING_MASK, facing.index() as u32)
+    }
+
     fn register_default_blocks(&mut self) {
         // Air
         self.register(BlockProperties {
@@ -140,5 +341,131 @@ impl BlockRegistry {
             debug_color: Color::srgb(0.2, 0.8, 0.2),
             ..Default::default()
         });
+
+        // Log
+        self.register(BlockProperties {
+            id: BlockId::LOG,
+            name: "Log".to_string(),
+            hardness: 2.0,
+            tool_type: ToolType::Axe,
+            is_solid: true,
+            debug_color: Color::srgb(0.4, 0.25, 0.1),
+            ..Default::default()
+        });
+
+        // Leaves
+        self.register(BlockProperties {
+            id: BlockId::LEAVES,
+            name: "Leaves".to_string(),
+            hardness: 0.2,
+            is_solid: true,
+            debug_color: Color::srgb(0.1, 0.5, 0.1),
+            ..Default::default()
+        });
+
+        // Furnace
+        self.register(BlockProperties {
+            id: BlockId::FURNACE,
+            name: "Furnace".to_string(),
+            hardness: 3.5,
+            tool_type: ToolType::Pickaxe,
+            required_tool: Some(ToolType::Pickaxe),
+            is_solid: true,
+            state_properties: vec![StateProperty::Facing, StateProperty::Powered],
+            debug_color: Color::srgb(0.35, 0.35, 0.38),
+            ..Default::default()
+        });
+
+        // Machine Casing
+        self.register(BlockProperties {
+            id: BlockId::MACHINE_CASING,
+            name: "Machine Casing".to_string(),
+            hardness: 5.0,
+            tool_type: ToolType::Pickaxe,
+            required_tool: Some(ToolType::Pickaxe),
+            is_solid: true,
+            is_multiblock_part: true,
+            tags: vec!["casing".to_string()],
+            debug_color: Color::srgb(0.55, 0.55, 0.6),
+            ..Default::default()
+        });
+
+        // Input Hatch
+        self.register(BlockProperties {
+            id: BlockId::INPUT_HATCH,
+            name: "Input Hatch".to_string(),
+            hardness: 5.0,
+            tool_type: ToolType::Pickaxe,
+            required_tool: Some(ToolType::Pickaxe),
+            is_solid: true,
+            is_multiblock_part: true,
+            tags: vec!["hatch".to_string()],
+            debug_color: Color::srgb(0.3, 0.45, 0.7),
+            ..Default::default()
+        });
+
+        // Output Hatch
+        self.register(BlockProperties {
+            id: BlockId::OUTPUT_HATCH,
+            name: "Output Hatch".to_string(),
+            hardness: 5.0,
+            tool_type: ToolType::Pickaxe,
+            required_tool: Some(ToolType::Pickaxe),
+            is_solid: true,
+            is_multiblock_part: true,
+            tags: vec!["hatch".to_string()],
+            debug_color: Color::srgb(0.7, 0.45, 0.2),
+            ..Default::default()
+        });
+
+        // Energy Port
+        self.register(BlockProperties {
+            id: BlockId::ENERGY_PORT,
+            name: "Energy Port".to_string(),
+            hardness: 5.0,
+            tool_type: ToolType::Pickaxe,
+            required_tool: Some(ToolType::Pickaxe),
+            is_solid: true,
+            is_multiblock_part: true,
+            tags: vec!["hatch".to_string()],
+            debug_color: Color::srgb(0.8, 0.75, 0.2),
+            ..Default::default()
+        });
+
+        // Blast Furnace
+        self.register(BlockProperties {
+            id: BlockId::BLAST_FURNACE,
+            name: "Blast Furnace".to_string(),
+            hardness: 5.0,
+            tool_type: ToolType::Pickaxe,
+            required_tool: Some(ToolType::Pickaxe),
+            is_solid: true,
+            is_multiblock_controller: true,
+            state_properties: vec![StateProperty::Facing, StateProperty::Powered],
+            debug_color: Color::srgb(0.45, 0.2, 0.15),
+            ..Default::default()
+        });
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_values_follow_declared_properties() {
+        let registry = BlockRegistry::new();
+        let values = StateValues { facing: Some(Direction::West), powered: Some(true), variant: None };
+
+        let furnace = registry.state_with(BlockId::FURNACE, values).unwrap();
+        assert_eq!(furnace.block(), BlockId::FURNACE);
+        assert_eq!(registry.state_values(furnace), values);
+        assert_eq!(registry.facing(registry.with_facing(furnace, Direction::Up)), Some(Direction::Up));
+
+        // Stone declares nothing, so it has a single state
+        assert!(registry.state_with(BlockId::STONE, values).is_none());
+        let stone = BlockState::from(BlockId::STONE);
+        assert_eq!(registry.with_facing(stone, Direction::East), stone);
+        assert_eq!(registry.state_values(stone), StateValues::default());
+    }
+}
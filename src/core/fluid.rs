@@ -0,0 +1,77 @@
+use crate::core::inventory::Transfer;
+
+/// Fluid ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FluidId(pub u16);
+
+impl FluidId {
+    pub const WATER: FluidId = FluidId(1);
+    pub const LAVA: FluidId = FluidId(2);
+
+    /// Look Up A Fluid By Name (case insensitive)
+    pub fn from_name(name: &str) -> Option<FluidId> {
+        match name.to_lowercase().as_str() {
+            "water" => Some(FluidId::WATER),
+            "lava" => Some(FluidId::LAVA),
+            _ => None,
+        }
+    }
+}
+
+/// Amount Of A Fluid (millibuckets, 1000 per block)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FluidStack {
+    pub fluid: FluidId,
+    pub amount: u32,
+}
+
+impl FluidStack {
+    pub fn new(fluid: FluidId, amount: u32) -> Self {
+        Self { fluid, amount }
+    }
+}
+
+/// Single Fluid Storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FluidTank {
+    pub contents: Option<FluidStack>,
+    pub capacity: u32,
+}
+
+impl FluidTank {
+    pub fn new(capacity: u32) -> Self {
+        Self { contents: None, capacity }
+    }
+
+    pub fn amount(&self) -> u32 {
+        self.contents.map_or(0, |stack| stack.amount)
+    }
+
+    /// Add Fluid, Returning The Amount Accepted (tanks never mix fluids)
+    pub fn fill(&mut self, stack: FluidStack, mode: Transfer) -> u32 {
+        if self.contents.is_some_and(|contents| contents.fluid != stack.fluid) {
+            return 0;
+        }
+
+        let accepted = stack.amount.min(self.capacity - self.amount());
+        if mode == Transfer::Execute && accepted > 0 {
+            self.contents = Some(FluidStack::new(stack.fluid, self.amount() + accepted));
+        }
+        accepted
+    }
+
+    /// Remove Up To `amount`, Returning What Was Drained
+    pub fn drain(&mut self, amount: u32, mode: Transfer) -> Option<FluidStack> {
+        let contents = self.contents?;
+        let drained = amount.min(contents.amount);
+        if drained == 0 {
+            return None;
+        }
+
+        if mode == Transfer::Execute {
+            let left = contents.amount - drained;
+            self.contents = (left > 0).then(|| FluidStack::new(contents.fluid, left));
+        }
+        Some(FluidStack::new(contents.fluid, drained))
+    }
+}
@@ -0,0 +1,337 @@
+use crate::core::item::{ItemId, ItemRegistry};
+use bevy::prelude::*;
+
+/// Player Inventory Size (hotbar is the first 9 slots)
+pub const PLAYER_INVENTORY_SIZE: usize = 36;
+
+/// Items In One Slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u32,
+    /// Uses spent on a tool, stacks only merge when equal
+    pub damage: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: ItemId, count: u32) -> Self {
+        Self { item, count, damage: 0 }
+    }
+
+    /// Whether Two Stacks Can Share A Slot
+    pub fn can_merge(&self, other: &ItemStack) -> bool {
+        self.item == other.item && self.damage == other.damage
+    }
+
+    fn with_count(self, count: u32) -> Self {
+        Self { count, ..self }
+    }
+}
+
+/// What A Slot Accepts From Inserts
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SlotFilter {
+    #[default]
+    Any,
+    Only(Vec<ItemId>),
+    /// Extract only, like machine outputs (owners fill it with `insert_owned`)
+    Output,
+}
+
+impl SlotFilter {
+    pub fn accepts(&self, item: ItemId) -> bool {
+        match self {
+            SlotFilter::Any => true,
+            SlotFilter::Only(items) => items.contains(&item),
+            SlotFilter::Output => false,
+        }
+    }
+}
+
+/// Whether A Transfer Changes The Inventory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transfer {
+    Execute,
+    /// Dry run, reports what would happen
+    Simulate,
+}
+
+/// Slot Based Item Storage
+///
+/// Used as a component on players and inside block entities. Inserts
+/// top up matching stacks before filling empty slots, never exceed the
+/// item's max stack size and respect slot filters. Every changed slot is
+/// recorded until `take_changed` is called.
+#[derive(Component, Debug, Clone, PartialEq, Default)]
+pub struct Inventory {
+    slots: Vec<Option<ItemStack>>,
+    filters: Vec<SlotFilter>,
+    changed: Vec<usize>,
+}
+
+impl Inventory {
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![None; size],
+            filters: vec![SlotFilter::Any; size],
+            changed: Vec::new(),
+        }
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Whether Every Slot Is Empty
+    pub fn has_no_items(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&ItemStack> {
+        self.slots.get(slot)?.as_ref()
+    }
+
+    /// Replace A Slot, Ignoring Its Filter
+    pub fn set(&mut self, slot: usize, stack: Option<ItemStack>) {
+        self.slots[slot] = stack.filter(|stack| stack.count > 0);
+        self.mark_changed(slot);
+    }
+
+    pub fn set_filter(&mut self, slot: usize, filter: SlotFilter) {
+        self.filters[slot] = filter;
+    }
+
+    pub fn filter(&self, slot: usize) -> &SlotFilter {
+        &self.filters[slot]
+    }
+
+    pub fn slots(&self) -> impl Iterator<Item = (usize, &ItemStack)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, stack)| stack.as_ref().map(|stack| (slot, stack)))
+    }
+
+    /// Total Count Of An Item Across All Slots
+    pub fn count(&self, item: ItemId) -> u32 {
+        self.slots().filter(|(_, stack)| stack.item == item).map(|(_, stack)| stack.count).sum()
+    }
+
+    /// Slots Changed Since The Last Call
+    pub fn take_changed(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.changed)
+    }
+
+    fn mark_changed(&mut self, slot: usize) {
+        if !self.changed.contains(&slot) {
+            self.changed.push(slot);
+        }
+    }
+
+    /// Insert Into One Slot, Returning What Did Not Fit
+    pub fn insert_into(
+        &mut self,
+        slot: usize,
+        stack: ItemStack,
+        registry: &ItemRegistry,
+        mode: Transfer,
+    ) -> Option<ItemStack> {
+        self.insert_slot(slot, stack, registry, mode, true)
+    }
+
+    /// Insert Anywhere, Returning What Did Not Fit
+    pub fn insert(&mut self, stack: ItemStack, registry: &ItemRegistry, mode: Transfer) -> Option<ItemStack> {
+        self.insert_any(stack, registry, mode, true)
+    }
+
+    /// Insert Anywhere As The Owner, Ignoring Slot Filters (machines filling their outputs)
+    pub fn insert_owned(&mut self, stack: ItemStack, registry: &ItemRegistry, mode: Transfer) -> Option<ItemStack> {
+        self.insert_any(stack, registry, mode, false)
+    }
+
+    fn insert_slot(
+        &mut self,
+        slot: usize,
+        stack: ItemStack,
+        registry: &ItemRegistry,
+        mode: Transfer,
+        filtered: bool,
+    ) -> Option<ItemStack> {
+        if stack.count == 0 {
+            return None;
+        }
+        if slot >= self.slot_count() || (filtered && !self.filters[slot].accepts(stack.item)) {
+            return Some(stack);
+        }
+
+        let max_stack = registry.max_stack(stack.item);
+        let (current, existing) = match self.slots[slot] {
+            None => (0, stack),
+            Some(existing) if existing.can_merge(&stack) => (existing.count, existing),
+            Some(_) => return Some(stack),
+        };
+
+        let moved = stack.count.min(max_stack.saturating_sub(current));
+        if moved == 0 {
+            return Some(stack);
+        }
+        if mode == Transfer::Execute {
+            self.slots[slot] = Some(existing.with_count(current + moved));
+            self.mark_changed(slot);
+        }
+
+        let left = stack.count - moved;
+        (left > 0).then(|| stack.with_count(left))
+    }
+
+    fn insert_any(&mut self, stack: ItemStack, registry: &ItemRegistry, mode: Transfer, filtered: bool) -> Option<ItemStack> {
+        // Simulations run on a copy so later slots see earlier ones filled
+        if mode == Transfer::Simulate {
+            return self.clone().insert_any(stack, registry, Transfer::Execute, filtered);
+        }
+
+        let mut remaining = stack;
+        // Top up matching stacks first, then fill empty slots
+        let merging: Vec<usize> = self
+            .slots()
+            .filter(|(_, existing)| existing.can_merge(&stack))
+            .map(|(slot, _)| slot)
+            .collect();
+        let empty = (0..self.slot_count()).filter(|slot| self.slots[*slot].is_none()).collect::<Vec<_>>();
+
+        for slot in merging.into_iter().chain(empty) {
+            match self.insert_slot(slot, remaining, registry, Transfer::Execute, filtered) {
+                Some(left) => remaining = left,
+                None => return None,
+            }
+        }
+        Some(remaining)
+    }
+
+    /// Take Up To `amount` From One Slot (filters never block extraction)
+    pub fn extract(&mut self, slot: usize, amount: u32, mode: Transfer) -> Option<ItemStack> {
+        let stack = (*self.slots.get(slot)?)?;
+        let taken = amount.min(stack.count);
+        if taken == 0 {
+            return None;
+        }
+
+        if mode == Transfer::Execute {
+            let left = stack.count - taken;
+            self.slots[slot] = (left > 0).then(|| stack.with_count(left));
+            self.mark_changed(slot);
+        }
+        Some(stack.with_count(taken))
+    }
+
+    /// Take Up To `amount` Of An Item From Any Slots, Returning The Count Taken
+    pub fn extract_item(&mut self, item: ItemId, amount: u32, mode: Transfer) -> u32 {
+        let mut taken = 0;
+        for slot in 0..self.slot_count() {
+            if taken == amount {
+                break;
+            }
+            if self.get(slot).is_some_and(|stack| stack.item == item) {
+                taken += self.extract(slot, amount - taken, mode).map_or(0, |stack| stack.count);
+            }
+        }
+        taken
+    }
+
+    /// Move Up To `amount` From A Slot Into Another Inventory, Returning The Count Moved
+    pub fn transfer_to(
+        &mut self,
+        slot: usize,
+        amount: u32,
+        target: &mut Inventory,
+        registry: &ItemRegistry,
+        mode: Transfer,
+    ) -> u32 {
+        let Some(offered) = self.extract(slot, amount, Transfer::Simulate) else {
+            return 0;
+        };
+
+        let left = target.insert(offered, registry, Transfer::Simulate).map_or(0, |stack| stack.count);
+        let moved = offered.count - left;
+        if mode == Transfer::Execute && moved > 0 {
+            let taken = self.extract(slot, moved, Transfer::Execute).unwrap();
+            target.insert(taken, registry, Transfer::Execute);
+        }
+        moved
+    }
+}
+
+/// Sent When Slots Of An Inventory Component Change
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub struct InventoryChanged {
+    pub entity: Entity,
+    pub slots: Vec<usize>,
+}
+
+/// Announce Changed Inventory Slots
+pub fn send_inventory_changes(
+    mut query: Query<(Entity, &mut Inventory), Changed<Inventory>>,
+    mut changes: MessageWriter<InventoryChanged>,
+) {
+    for (entity, mut inventory) in query.iter_mut() {
+        let slots = inventory.bypass_change_detection().take_changed();
+        if !slots.is_empty() {
+            changes.write(InventoryChanged { entity, slots });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::{BlockId, BlockRegistry};
+
+    fn registry() -> ItemRegistry {
+        ItemRegistry::new(&BlockRegistry::new())
+    }
+
+    #[test]
+    fn test_insert_merges_before_filling_empty_slots() {
+        let registry = registry();
+        let stone = ItemId::from_block(BlockId::STONE);
+        let mut inventory = Inventory::new(3);
+        inventory.set(2, Some(ItemStack::new(stone, 60)));
+        inventory.take_changed();
+
+        assert_eq!(inventory.insert(ItemStack::new(stone, 10), &registry, Transfer::Execute), None);
+        assert_eq!(inventory.get(2).unwrap().count, 64);
+        assert_eq!(inventory.get(0).unwrap().count, 6);
+        assert_eq!(inventory.take_changed(), vec![2, 0]);
+
+        // Tools never stack
+        let pickaxe = ItemStack::new(ItemId::STONE_PICKAXE, 3);
+        let left = inventory.insert(pickaxe, &registry, Transfer::Execute).unwrap();
+        assert_eq!(left.count, 2);
+        assert_eq!(inventory.get(1), Some(&ItemStack::new(ItemId::STONE_PICKAXE, 1)));
+    }
+
+    #[test]
+    fn test_filters_and_simulation() {
+        let registry = registry();
+        let stone = ItemId::from_block(BlockId::STONE);
+        let dirt = ItemId::from_block(BlockId::DIRT);
+        let mut inventory = Inventory::new(2);
+        inventory.set_filter(0, SlotFilter::Only(vec![dirt]));
+        inventory.set_filter(1, SlotFilter::Output);
+
+        let stones = ItemStack::new(stone, 5);
+        assert_eq!(inventory.insert(stones, &registry, Transfer::Execute), Some(stones));
+        assert_eq!(inventory.insert(ItemStack::new(dirt, 100), &registry, Transfer::Simulate).unwrap().count, 36);
+        assert!(inventory.has_no_items());
+        assert!(inventory.take_changed().is_empty());
+
+        // Output slots still hand their items out
+        inventory.set(1, Some(stones));
+        let mut target = Inventory::new(1);
+        assert_eq!(inventory.transfer_to(1, 3, &mut target, &registry, Transfer::Simulate), 3);
+        assert_eq!(inventory.count(stone), 5);
+        assert_eq!(inventory.transfer_to(1, 3, &mut target, &registry, Transfer::Execute), 3);
+        assert_eq!(inventory.count(stone), 2);
+        assert_eq!(target.count(stone), 3);
+    }
+}
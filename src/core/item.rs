@@ -0,0 +1,222 @@
+use crate::core::block::{BlockId, BlockRegistry, ToolType};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Item ID
+///
+/// Ids below `FIRST_ITEM` belong to the block item of the block with the
+/// same id, so block items never need to be looked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ItemId(pub u32);
+
+impl ItemId {
+    /// First id not tied to a block
+    pub const FIRST_ITEM: u32 = 1 << 16;
+
+    pub const WOODEN_PICKAXE: ItemId = ItemId(Self::FIRST_ITEM);
+    pub const STONE_PICKAXE: ItemId = ItemId(Self::FIRST_ITEM + 1);
+    pub const STONE_SHOVEL: ItemId = ItemId(Self::FIRST_ITEM + 2);
+    pub const STONE_AXE: ItemId = ItemId(Self::FIRST_ITEM + 3);
+    pub const CHARCOAL: ItemId = ItemId(Self::FIRST_ITEM + 4);
+
+    /// Block Item Id For A Block
+    pub fn from_block(block: BlockId) -> ItemId {
+        ItemId(block.0 as u32)
+    }
+}
+
+/// Tool Material Tier (higher tiers mine faster)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum ToolTier {
+    #[default]
+    None,
+    Wood,
+    Stone,
+    Iron,
+    Diamond,
+}
+
+impl ToolTier {
+    /// Mining Speed Multiplier On Matching Blocks
+    pub fn speed(self) -> f32 {
+        match self {
+            ToolTier::None => 1.0,
+            ToolTier::Wood => 2.0,
+            ToolTier::Stone => 4.0,
+            ToolTier::Iron => 6.0,
+            ToolTier::Diamond => 8.0,
+        }
+    }
+}
+
+/// Item Properties
+#[derive(Debug, Clone)]
+pub struct ItemProperties {
+    pub id: ItemId,
+    pub name: String,
+    pub max_stack: u32,
+    pub tool_type: ToolType,
+    pub tool_tier: ToolTier,
+    /// Uses before breaking (None for items that never wear out)
+    pub durability: Option<u32>,
+    /// Block placed when used on a face
+    pub places_block: Option<BlockId>,
+}
+
+impl Default for ItemProperties {
+    fn default() -> Self {
+        Self {
+            id: ItemId(0),
+            name: "Unknown".to_string(),
+            max_stack: 64,
+            tool_type: ToolType::None,
+            tool_tier: ToolTier::None,
+            durability: None,
+            places_block: None,
+        }
+    }
+}
+
+impl ItemProperties {
+    pub fn is_tool(&self) -> bool {
+        self.tool_type != ToolType::None
+    }
+}
+
+/// Global Registry for Items
+#[derive(Resource, Default, Clone)]
+pub struct ItemRegistry {
+    items: HashMap<ItemId, ItemProperties>,
+    by_name: HashMap<String, ItemId>,
+}
+
+impl ItemRegistry {
+    /// Block Items For Every Registered Block Plus The Default Items
+    pub fn new(blocks: &BlockRegistry) -> Self {
+        let mut registry = Self::default();
+        registry.register_block_items(blocks);
+        registry.register_default_items();
+        registry
+    }
+
+    pub fn register(&mut self, props: ItemProperties) {
+        self.by_name.insert(props.name.to_lowercase(), props.id);
+        self.items.insert(props.id, props);
+    }
+
+    /// Register A Block Item For Every Block Without One (air has none)
+    pub fn register_block_items(&mut self, blocks: &BlockRegistry) {
+        for block in blocks.iter().filter(|block| block.id != BlockId::AIR) {
+            let id = ItemId::from_block(block.id);
+            if self.items.contains_key(&id) {
+                continue;
+            }
+
+            self.register(ItemProperties {
+                id,
+                name: block.name.clone(),
+                places_block: Some(block.id),
+                ..Default::default()
+            });
+        }
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<&ItemProperties> {
+        self.items.get(&id)
+    }
+
+    /// Look Up An Item By Name (case insensitive)
+    pub fn find(&self, name: &str) -> Option<ItemId> {
+        self.by_name.get(&name.to_lowercase()).copied()
+    }
+
+    /// Item That Places A Block, If Registered
+    pub fn block_item(&self, block: BlockId) -> Option<ItemId> {
+        let id = ItemId::from_block(block);
+        self.items.contains_key(&id).then_some(id)
+    }
+
+    pub fn max_stack(&self, id: ItemId) -> u32 {
+        self.get(id).map_or(1, |props| props.max_stack)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ItemProperties> {
+        self.items.values()
+    }
+
+    fn register_default_items(&mut self) {
+        // Wooden Pickaxe
+        self.register(ItemProperties {
+            id: ItemId::WOODEN_PICKAXE,
+            name: "Wooden Pickaxe".to_string(),
+            max_stack: 1,
+            tool_type: ToolType::Pickaxe,
+            tool_tier: ToolTier::Wood,
+            durability: Some(60),
+            ..Default::default()
+        });
+
+        // Stone Pickaxe
+        self.register(ItemProperties {
+            id: ItemId::STONE_PICKAXE,
+            name: "Stone Pickaxe".to_string(),
+            max_stack: 1,
+            tool_type: ToolType::Pickaxe,
+            tool_tier: ToolTier::Stone,
+            durability: Some(132),
+            ..Default::default()
+        });
+
+        // Stone Shovel
+        self.register(ItemProperties {
+            id: ItemId::STONE_SHOVEL,
+            name: "Stone Shovel".to_string(),
+            max_stack: 1,
+            tool_type: ToolType::Shovel,
+            tool_tier: ToolTier::Stone,
+            durability: Some(132),
+            ..Default::default()
+        });
+
+        // Stone Axe
+        self.register(ItemProperties {
+            id: ItemId::STONE_AXE,
+            name: "Stone Axe".to_string(),
+            max_stack: 1,
+            tool_type: ToolType::Axe,
+            tool_tier: ToolTier::Stone,
+            durability: Some(132),
+            ..Default::default()
+        });
+
+        // Charcoal
+        self.register(ItemProperties {
+            id: ItemId::CHARCOAL,
+            name: "Charcoal".to_string(),
+            ..Default::default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_block_gets_an_item() {
+        let blocks = BlockRegistry::new();
+        let items = ItemRegistry::new(&blocks);
+
+        for block in blocks.iter().filter(|block| block.id != BlockId::AIR) {
+            let item = items.block_item(block.id).unwrap();
+            assert_eq!(items.get(item).unwrap().places_block, Some(block.id));
+        }
+        assert!(items.block_item(BlockId::AIR).is_none());
+
+        let pickaxe = items.get(ItemId::STONE_PICKAXE).unwrap();
+        assert!(pickaxe.is_tool());
+        assert_eq!(pickaxe.max_stack, 1);
+        assert_eq!(items.find("stone pickaxe"), Some(ItemId::STONE_PICKAXE));
+        assert_eq!(items.find("Furnace"), Some(ItemId::from_block(BlockId::FURNACE)));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::core::block::{BlockId, BlockRegistry, ToolType};
+use crate::core::inventory::ItemStack;
+use crate::core::item::{ItemId, ItemRegistry};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Seedable Random Numbers For Loot Rolls (SplitMix64)
+#[derive(Resource, Debug, Clone)]
+pub struct LootRng(u64);
+
+impl Default for LootRng {
+    fn default() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64);
+        Self::new(seed)
+    }
+}
+
+impl LootRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform In `0.0..1.0`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform In `min..=max`
+    pub fn range(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min {
+            return min;
+        }
+        min + (self.next_u64() % (max - min + 1) as u64) as u32
+    }
+}
+
+/// One Possible Drop
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootEntry {
+    pub item: ItemId,
+    pub min: u32,
+    pub max: u32,
+    /// 1.0 for guaranteed drops
+    pub chance: f32,
+    /// Only drops when broken with this tool
+    pub tool: Option<ToolType>,
+}
+
+impl LootEntry {
+    /// Guaranteed Drop
+    pub fn always(item: ItemId, count: u32) -> Self {
+        Self {
+            item,
+            min: count,
+            max: count,
+            chance: 1.0,
+            tool: None,
+        }
+    }
+
+    /// Single Item Dropped With A Probability
+    pub fn chance(item: ItemId, chance: f32) -> Self {
+        Self {
+            chance,
+            ..Self::always(item, 1)
+        }
+    }
+
+    pub fn count(self, min: u32, max: u32) -> Self {
+        Self { min, max, ..self }
+    }
+
+    pub fn requires(self, tool: ToolType) -> Self {
+        Self { tool: Some(tool), ..self }
+    }
+}
+
+/// Drops Of One Block
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LootTable {
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub fn new(entries: Vec<LootEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Roll Every Entry, Merging Drops Of The Same Item
+    ///
+    /// Nothing drops unless the block was harvested, meaning its
+    /// `required_tool` was used.
+    pub fn roll(&self, tool: ToolType, harvested: bool, rng: &mut LootRng) -> Vec<ItemStack> {
+        let mut drops: Vec<ItemStack> = Vec::new();
+        if !harvested {
+            return drops;
+        }
+
+        for entry in &self.entries {
+            if entry.tool.is_some_and(|required| required != tool) {
+                continue;
+            }
+            if entry.chance < 1.0 && rng.next_f32() >= entry.chance {
+                continue;
+            }
+
+            let count = rng.range(entry.min, entry.max);
+            if count == 0 {
+                continue;
+            }
+            match drops.iter_mut().find(|stack| stack.item == entry.item) {
+                Some(stack) => stack.count += count,
+                None => drops.push(ItemStack::new(entry.item, count)),
+            }
+        }
+        drops
+    }
+}
+
+/// Loot Table Per Block
+///
+/// Blocks without an explicit table drop their own block item.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LootTables {
+    tables: HashMap<BlockId, LootTable>,
+}
+
+impl LootTables {
+    pub fn new(blocks: &BlockRegistry, items: &ItemRegistry) -> Self {
+        let mut tables = Self::default();
+        for block in blocks.iter() {
+            if let Some(item) = items.block_item(block.id) {
+                tables.set(block.id, LootTable::new(vec![LootEntry::always(item, 1)]));
+            }
+        }
+        tables.register_default_tables(items);
+        tables
+    }
+
+    pub fn set(&mut self, block: BlockId, table: LootTable) {
+        self.tables.insert(block, table);
+    }
+
+    pub fn get(&self, block: BlockId) -> Option<&LootTable> {
+        self.tables.get(&block)
+    }
+
+    fn register_default_tables(&mut self, items: &ItemRegistry) {
+        // Grass turns to dirt
+        if let Some(dirt) = items.block_item(BlockId::DIRT) {
+            self.set(BlockId::GRASS, LootTable::new(vec![LootEntry::always(dirt, 1)]));
+        }
+
+        // Leaves only sometimes drop, more reliably when cut with an axe
+        if let Some(leaves) = items.block_item(BlockId::LEAVES) {
+            self.set(
+                BlockId::LEAVES,
+                LootTable::new(vec![
+                    LootEntry::chance(leaves, 0.2),
+                    LootEntry::chance(leaves, 0.5).requires(ToolType::Axe),
+                ]),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolls_are_deterministic_per_seed() {
+        let table = LootTable::new(vec![
+            LootEntry::always(ItemId(1), 1),
+            LootEntry::chance(ItemId(2), 0.5).count(1, 3),
+            LootEntry::always(ItemId(3), 2).requires(ToolType::Pickaxe),
+        ]);
+
+        let rolls = |seed| {
+            let mut rng = LootRng::new(seed);
+            (0..50).map(|_| table.roll(ToolType::None, true, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(rolls(7), rolls(7));
+
+        let all = rolls(7).concat();
+        assert_eq!(all.iter().filter(|stack| stack.item == ItemId(1)).count(), 50);
+        let chanced: Vec<_> = all.iter().filter(|stack| stack.item == ItemId(2)).collect();
+        assert!(!chanced.is_empty() && chanced.len() < 50);
+        assert!(chanced.iter().all(|stack| (1..=3).contains(&stack.count)));
+        // Wrong tool
+        assert!(all.iter().all(|stack| stack.item != ItemId(3)));
+
+        let mut rng = LootRng::new(7);
+        assert!(table.roll(ToolType::Pickaxe, false, &mut rng).is_empty());
+        assert!(table.roll(ToolType::Pickaxe, true, &mut rng).contains(&ItemStack::new(ItemId(3), 2)));
+    }
+
+    #[test]
+    fn test_blocks_drop_their_items_by_default() {
+        let blocks = BlockRegistry::new();
+        let items = ItemRegistry::new(&blocks);
+        let tables = LootTables::new(&blocks, &items);
+        let mut rng = LootRng::new(1);
+
+        let stone = tables.get(BlockId::STONE).unwrap().roll(ToolType::Pickaxe, true, &mut rng);
+        assert_eq!(stone, vec![ItemStack::new(ItemId::from_block(BlockId::STONE), 1)]);
+        let grass = tables.get(BlockId::GRASS).unwrap().roll(ToolType::None, true, &mut rng);
+        assert_eq!(grass, vec![ItemStack::new(ItemId::from_block(BlockId::DIRT), 1)]);
+        assert!(tables.get(BlockId::AIR).is_none());
+    }
+}
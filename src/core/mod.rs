@@ -1,4 +1,7 @@
 pub mod block;
+pub mod fluid;
+pub mod inventory;
+pub mod item;
+pub mod loot;
 pub mod position;
-
-// Items/inventory modules will be added later
\ No newline at end of file
+pub mod tick;
\ No newline at end of file
@@ -4,6 +4,9 @@ use std::ops::Add;
 /// World Wrap
 pub const WORLD_SIZE: i32 = 1024;
 pub const WORLD_HEIGHT: i32 = 512;
+/// Lowest And Highest Block Y (positions are clamped to these)
+pub const MIN_BLOCK_Y: i32 = -500;
+pub const MAX_BLOCK_Y: i32 = 499;
 pub const CHUNK_SIZE: u8 = 32;
 pub const CHUNK_SIZE_I32: i32 = CHUNK_SIZE as i32;
 
@@ -52,13 +55,18 @@ impl ChunkPos {
 
     /// Neighbor Chunk Positions
     pub fn neighbors(&self) -> [ChunkPos; 6] {
+        self.scaled_neighbors(1)
+    }
+
+    /// Face Neighbors `step` Chunks Away (LOD chunks span several positions)
+    pub fn scaled_neighbors(&self, step: i32) -> [ChunkPos; 6] {
         [
-            ChunkPos::new(self.x + 1, self.y, self.z),
-            ChunkPos::new(self.x - 1, self.y, self.z),
-            ChunkPos::new(self.x, self.y + 1, self.z),
-            ChunkPos::new(self.x, self.y - 1, self.z),
-            ChunkPos::new(self.x, self.y, self.z + 1),
-            ChunkPos::new(self.x, self.y, self.z - 1),
+            ChunkPos::new(self.x + step, self.y, self.z),
+            ChunkPos::new(self.x - step, self.y, self.z),
+            ChunkPos::new(self.x, self.y + step, self.z),
+            ChunkPos::new(self.x, self.y - step, self.z),
+            ChunkPos::new(self.x, self.y, self.z + step),
+            ChunkPos::new(self.x, self.y, self.z - step),
         ]
     }
 }
@@ -75,7 +83,7 @@ impl BlockPos {
     pub fn new(x: i32, y: i32, z: i32) -> Self {
         Self {
             x: ChunkPos::wrap_horizontal(x),
-            y: y.clamp(-500, 499),
+            y: y.clamp(MIN_BLOCK_Y, MAX_BLOCK_Y),
             z: ChunkPos::wrap_horizontal(z),
         }
     }
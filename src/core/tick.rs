@@ -0,0 +1,171 @@
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Default Simulation Rate
+pub const TICKS_PER_SECOND: u32 = 20;
+
+/// Most Ticks Run In One Frame Before The Backlog Is Dropped
+pub const MAX_CATCH_UP_TICKS: u32 = 10;
+
+/// Schedule For Gameplay Simulation, Run At A Fixed Tick Rate
+///
+/// Everything that should behave the same regardless of frame rate
+/// (machines, fluids, random ticks, transport) belongs here instead of
+/// `Update`. Durations in ticks are always counted against this schedule.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulationTick;
+
+/// Order Of Work Inside One Simulation Tick
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    RandomTicks,
+    Fluids,
+    Transport,
+    Machines,
+}
+
+/// Simulation Tick Counter And Rate
+#[derive(Resource, Debug, Clone)]
+pub struct SimulationClock {
+    /// Ticks run since startup
+    pub tick: u64,
+    pub tick_rate: u32,
+    pub max_catch_up: u32,
+    pub paused: bool,
+    /// Ticks dropped because frames fell too far behind
+    pub skipped: u64,
+    accumulator: Duration,
+    pending_steps: u32,
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self::new(TICKS_PER_SECOND)
+    }
+}
+
+impl SimulationClock {
+    pub fn new(tick_rate: u32) -> Self {
+        Self {
+            tick: 0,
+            tick_rate: tick_rate.max(1),
+            max_catch_up: MAX_CATCH_UP_TICKS,
+            paused: false,
+            skipped: 0,
+            accumulator: Duration::ZERO,
+            pending_steps: 0,
+        }
+    }
+
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_secs(1) / self.tick_rate.max(1)
+    }
+
+    /// Pause Or Resume (no time builds up while paused)
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.accumulator = Duration::ZERO;
+        self.pending_steps = 0;
+    }
+
+    /// Run A Single Tick On The Next Frame While Paused
+    pub fn step(&mut self) {
+        if self.paused {
+            self.pending_steps += 1;
+        }
+    }
+
+    /// Ticks Due After A Frame Of `delta`
+    pub fn advance(&mut self, delta: Duration) -> u32 {
+        if self.paused {
+            return std::mem::take(&mut self.pending_steps);
+        }
+
+        self.accumulator += delta;
+        let tick = self.tick_duration();
+        let mut ticks = 0;
+        while self.accumulator >= tick && ticks < self.max_catch_up {
+            self.accumulator -= tick;
+            ticks += 1;
+        }
+
+        // Too far behind, drop whole ticks instead of spiralling
+        if self.accumulator >= tick {
+            let behind = self.accumulator.as_nanos() / tick.as_nanos();
+            self.skipped += behind as u64;
+            self.accumulator -= tick * behind as u32;
+        }
+        ticks
+    }
+}
+
+/// Run The Simulation Schedule Once For Every Tick Due This Frame
+pub fn run_simulation(world: &mut World) {
+    let delta = world.resource::<Time>().delta();
+    let ticks = world.resource_mut::<SimulationClock>().advance(delta);
+
+    for _ in 0..ticks {
+        world.resource_mut::<SimulationClock>().tick += 1;
+        if world.try_run_schedule(SimulationTick).is_err() {
+            return;
+        }
+    }
+}
+
+/// Pause (P) And Single Step (Period) The Simulation
+pub fn simulation_controls(keys: Res<ButtonInput<KeyCode>>, mut clock: ResMut<SimulationClock>) {
+    if keys.just_pressed(KeyCode::KeyP) {
+        let paused = !clock.paused;
+        clock.set_paused(paused);
+        info!("Simulation {} at tick {}", if paused { "paused" } else { "resumed" }, clock.tick);
+    }
+    if keys.just_pressed(KeyCode::Period) && clock.paused {
+        clock.step();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_up_limit_and_pause() {
+        let mut clock = SimulationClock::new(20);
+        assert_eq!(clock.advance(Duration::from_millis(30)), 0);
+        assert_eq!(clock.advance(Duration::from_millis(30)), 1);
+
+        // A long frame runs at most the catch-up limit and drops the rest
+        assert_eq!(clock.advance(Duration::from_secs(2)), MAX_CATCH_UP_TICKS);
+        assert_eq!(clock.skipped, 30);
+        assert!(clock.accumulator < clock.tick_duration());
+
+        clock.set_paused(true);
+        assert_eq!(clock.advance(Duration::from_secs(1)), 0);
+        clock.step();
+        clock.step();
+        assert_eq!(clock.advance(Duration::from_secs(1)), 2);
+        assert_eq!(clock.advance(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn test_run_simulation_counts_ticks() {
+        #[derive(Resource, Default)]
+        struct Runs(u32);
+
+        let mut world = World::new();
+        world.init_resource::<Runs>();
+        world.insert_resource(SimulationClock::new(20));
+        let mut schedule = Schedule::new(SimulationTick);
+        schedule.add_systems(|mut runs: ResMut<Runs>| runs.0 += 1);
+        world.add_schedule(schedule);
+
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(160));
+        world.insert_resource(time);
+        run_simulation(&mut world);
+
+        assert_eq!(world.resource::<Runs>().0, 3);
+        assert_eq!(world.resource::<SimulationClock>().tick, 3);
+    }
+}
@@ -2,5 +2,5 @@ pub mod core;
 pub mod world;
 pub mod voxel;
 pub mod player;
-
-// Multiblock and machine modules will be added later
\ No newline at end of file
+pub mod machine;
+pub mod multiblock;
\ No newline at end of file
@@ -0,0 +1,2 @@
+pub mod processing;
+pub mod recipe;
\ No newline at end of file
@@ -0,0 +1,494 @@
+use crate::core::block::BlockId;
+use crate::core::fluid::{FluidStack, FluidTank};
+use crate::core::inventory::{Inventory, ItemStack, SlotFilter, Transfer};
+use crate::core::item::ItemRegistry;
+use crate::core::loot::LootRng;
+use crate::machine::recipe::{MachineCategory, Recipe, RecipeId, RecipeRegistry};
+use crate::world::chunk::{BlockEntity, Chunk};
+use crate::world::chunk_manager::ChunkManager;
+use crate::world::events::{BlockChanged, ItemsDropped};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// What A Machine Did On Its Last Tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MachineStatus {
+    /// No recipe matches the inputs
+    #[default]
+    Idle,
+    Working,
+    /// Outputs have no room for the results
+    Blocked,
+    /// Not enough energy buffered for the next tick
+    NoPower,
+}
+
+/// Layout Of A Machine Block
+#[derive(Debug, Clone, PartialEq)]
+pub struct MachineSpec {
+    pub category: MachineCategory,
+    pub input_slots: usize,
+    pub output_slots: usize,
+    pub input_tanks: usize,
+    pub output_tanks: usize,
+    pub tank_capacity: u32,
+    /// Zero runs recipes unpowered
+    pub energy_capacity: u32,
+}
+
+/// Machine Layout Per Block
+#[derive(Resource, Debug, Clone)]
+pub struct MachineRegistry {
+    machines: HashMap<BlockId, MachineSpec>,
+}
+
+impl Default for MachineRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            machines: HashMap::new(),
+        };
+
+        // Furnace
+        registry.register(
+            BlockId::FURNACE,
+            MachineSpec {
+                category: MachineCategory::Smelting,
+                input_slots: 1,
+                output_slots: 2,
+                input_tanks: 0,
+                output_tanks: 0,
+                tank_capacity: 0,
+                // Unpowered until something generates energy
+                energy_capacity: 0,
+            },
+        );
+        registry
+    }
+}
+
+impl MachineRegistry {
+    pub fn register(&mut self, block: BlockId, spec: MachineSpec) {
+        self.machines.insert(block, spec);
+    }
+
+    pub fn get(&self, block: BlockId) -> Option<&MachineSpec> {
+        self.machines.get(&block)
+    }
+}
+
+/// Machine Block Entity
+///
+/// Inputs are only consumed when a run finishes, so the current recipe
+/// can always be found again from the inputs (after loading, too).
+/// Changing the inputs to something else restarts the progress, but a
+/// loaded machine keeps it while its recipe is looked up again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Machine {
+    pub category: MachineCategory,
+    pub input: Inventory,
+    /// Extract only for everyone but the machine
+    pub output: Inventory,
+    pub input_tanks: Vec<FluidTank>,
+    pub output_tanks: Vec<FluidTank>,
+    pub recipe: Option<RecipeId>,
+    /// Ticks spent on the current recipe
+    pub progress: u32,
+    pub energy: u32,
+    pub energy_capacity: u32,
+    pub status: MachineStatus,
+}
+
+impl Machine {
+    pub fn new(spec: &MachineSpec) -> Self {
+        let mut output = Inventory::new(spec.output_slots);
+        for slot in 0..spec.output_slots {
+            output.set_filter(slot, SlotFilter::Output);
+        }
+
+        Self {
+            category: spec.category,
+            input: Inventory::new(spec.input_slots),
+            output,
+            input_tanks: vec![FluidTank::new(spec.tank_capacity); spec.input_tanks],
+            output_tanks: vec![FluidTank::new(spec.tank_capacity); spec.output_tanks],
+            recipe: None,
+            progress: 0,
+            energy: 0,
+            energy_capacity: spec.energy_capacity,
+            status: MachineStatus::Idle,
+        }
+    }
+
+    /// Buffer Energy, Returning The Amount Accepted
+    pub fn receive_energy(&mut self, amount: u32) -> u32 {
+        let accepted = amount.min(self.energy_capacity.saturating_sub(self.energy));
+        self.energy += accepted;
+        accepted
+    }
+
+    /// Run One Simulation Tick
+    pub fn tick(&mut self, recipes: &RecipeRegistry, items: &ItemRegistry, rng: &mut LootRng) -> MachineStatus {
+        self.status = self.advance(recipes, items, rng);
+        self.status
+    }
+
+    fn advance(&mut self, recipes: &RecipeRegistry, items: &ItemRegistry, rng: &mut LootRng) -> MachineStatus {
+        let stacks: Vec<ItemStack> = self.input.slots().map(|(_, stack)| *stack).collect();
+        let fluids: Vec<FluidStack> = self.input_tanks.iter().filter_map(|tank| tank.contents).collect();
+
+        let current = self.recipe.filter(|id| {
+            recipes
+                .get(*id)
+                .is_some_and(|recipe| recipe.category == self.category && recipe.matches(&stacks, &fluids))
+        });
+        let Some(id) = current.or_else(|| recipes.find(self.category, &stacks, &fluids)) else {
+            self.recipe = None;
+            self.progress = 0;
+            return MachineStatus::Idle;
+        };
+        // No recipe yet after loading, the progress belongs to this one
+        if self.recipe.is_some_and(|previous| previous != id) {
+            self.progress = 0;
+        }
+        self.recipe = Some(id);
+        let recipe = recipes.get(id).unwrap();
+
+        if !self.outputs_fit(recipe, items) {
+            return MachineStatus::Blocked;
+        }
+
+        if self.energy_capacity > 0 {
+            let cost = recipe.energy_per_tick();
+            if self.energy < cost {
+                return MachineStatus::NoPower;
+            }
+            self.energy -= cost;
+        }
+        self.progress += 1;
+
+        if self.progress >= recipe.duration {
+            self.finish(recipe, items, rng);
+            self.progress = 0;
+        }
+        MachineStatus::Working
+    }
+
+    /// Whether Every Output, Including Every Byproduct, Has Room
+    fn outputs_fit(&self, recipe: &Recipe, items: &ItemRegistry) -> bool {
+        let mut output = self.output.clone();
+        let stacks = recipe.item_outputs.iter().chain(recipe.byproducts.iter().map(|byproduct| &byproduct.stack));
+        for stack in stacks {
+            if output.insert_owned(*stack, items, Transfer::Execute).is_some() {
+                return false;
+            }
+        }
+
+        let mut tanks = self.output_tanks.clone();
+        recipe.fluid_outputs.iter().all(|stack| fill_tanks(&mut tanks, *stack) == stack.amount)
+    }
+
+    fn finish(&mut self, recipe: &Recipe, items: &ItemRegistry, rng: &mut LootRng) {
+        for input in &recipe.item_inputs {
+            self.input.extract_item(input.item, input.count, Transfer::Execute);
+        }
+        for input in &recipe.fluid_inputs {
+            let mut left = input.amount;
+            for tank in self.input_tanks.iter_mut().filter(|tank| tank.contents.is_some_and(|c| c.fluid == input.fluid)) {
+                left -= tank.drain(left, Transfer::Execute).map_or(0, |drained| drained.amount);
+            }
+        }
+
+        for stack in &recipe.item_outputs {
+            self.output.insert_owned(*stack, items, Transfer::Execute);
+        }
+        for byproduct in &recipe.byproducts {
+            if rng.next_f32() < byproduct.chance {
+                self.output.insert_owned(byproduct.stack, items, Transfer::Execute);
+            }
+        }
+        for stack in &recipe.fluid_outputs {
+            fill_tanks(&mut self.output_tanks, *stack);
+        }
+    }
+}
+
+/// Spread Fluid Over Tanks, Returning The Amount Stored
+fn fill_tanks(tanks: &mut [FluidTank], stack: FluidStack) -> u32 {
+    let mut stored = 0;
+    for tank in tanks.iter_mut() {
+        let left = FluidStack::new(stack.fluid, stack.amount - stored);
+        stored += tank.fill(left, Transfer::Execute);
+        if stored == stack.amount {
+            break;
+        }
+    }
+    stored
+}
+
+/// Create And Remove Machine Block Entities As Machine Blocks Change
+///
+/// A removed machine spills its items where the block was. Fluids have
+/// nowhere to go yet and vanish with it.
+pub fn attach_machines(
+    mut changes: MessageReader<BlockChanged>,
+    mut spilled: MessageWriter<ItemsDropped>,
+    machines: Res<MachineRegistry>,
+    chunk_manager: Res<ChunkManager>,
+    mut chunks: Query<&mut Chunk>,
+) {
+    for change in changes.read() {
+        if change.old.block() == change.new.block() {
+            continue;
+        }
+        let Some(mut chunk) = chunk_manager
+            .get_chunk_entity(change.pos.chunk_pos())
+            .and_then(|entity| chunks.get_mut(entity).ok())
+        else {
+            continue;
+        };
+
+        let local = change.pos.local_pos();
+        if let Some(BlockEntity::Machine(machine)) = chunk.block_entities.get(&local) {
+            let stacks: Vec<ItemStack> = machine
+                .input
+                .slots()
+                .chain(machine.output.slots())
+                .map(|(_, stack)| *stack)
+                .collect();
+            chunk.block_entities.remove(&local);
+            if !stacks.is_empty() {
+                spilled.write(ItemsDropped { pos: change.pos, stacks });
+            }
+        }
+        if let Some(spec) = machines.get(change.new.block()) {
+            chunk.block_entities.insert(local, BlockEntity::Machine(Box::new(Machine::new(spec))));
+        }
+    }
+}
+
+/// Give Machine Blocks In Newly Loaded Chunks Their Missing Machines
+///
+/// Older saves dropped machine block entities, and `attach_machines` only
+/// sees blocks being changed, so loading is the only chance to add them.
+pub fn attach_loaded_machines(machines: Res<MachineRegistry>, mut chunks: Query<&mut Chunk, Added<Chunk>>) {
+    for mut chunk in chunks.iter_mut() {
+        // Most chunks hold no machine blocks at all, skip scanning them
+        let has_machines = chunk.blocks().palette().iter().any(|state| machines.get(state.block()).is_some());
+        if chunk.depth != 0 || !has_machines {
+            continue;
+        }
+
+        let missing: Vec<_> = chunk
+            .iter_blocks()
+            .filter(|(local, _)| !chunk.block_entities.contains_key(local))
+            .filter_map(|(local, block)| machines.get(block).map(|spec| (local, spec)))
+            .collect();
+        // A fresh machine never changes how a chunk looks
+        let chunk = chunk.bypass_change_detection();
+        for (local, spec) in missing {
+            chunk.block_entities.insert(local, BlockEntity::Machine(Box::new(Machine::new(spec))));
+        }
+    }
+}
+
+/// Advance Every Loaded Machine By One Tick
+pub fn process_machines(
+    recipes: Res<RecipeRegistry>,
+    items: Res<ItemRegistry>,
+    mut rng: ResMut<LootRng>,
+    mut chunks: Query<&mut Chunk>,
+) {
+    for mut chunk in chunks.iter_mut() {
+        if chunk.block_entities.is_empty() {
+            continue;
+        }
+
+        // Machine state never changes how a chunk looks, so skip remeshing
+        let chunk = chunk.bypass_change_detection();
+        let mut changed = false;
+        for entity in chunk.block_entities.values_mut() {
+            if let BlockEntity::Machine(machine) = entity {
+                let before = (machine.status, machine.progress, machine.energy);
+                machine.tick(&recipes, &items, &mut rng);
+                changed |= before != (machine.status, machine.progress, machine.energy);
+            }
+        }
+        if changed {
+            chunk.mark_needs_save();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockRegistry;
+    use crate::core::item::ItemId;
+    use crate::core::position::{BlockPos, ChunkPos, LocalPos};
+    use crate::world::events::BlockChangeCause;
+    use crate::machine::recipe::Byproduct;
+    use crate::world::region::RegionStorage;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn setup() -> (RecipeRegistry, ItemRegistry, Machine) {
+        let items = ItemRegistry::new(&BlockRegistry::new());
+        let mut recipes = RecipeRegistry::default();
+        recipes.register(Recipe {
+            id: "smelt_charcoal".to_string(),
+            category: MachineCategory::Smelting,
+            item_inputs: vec![ItemStack::new(ItemId::from_block(BlockId::LOG), 1)],
+            fluid_inputs: Vec::new(),
+            item_outputs: vec![ItemStack::new(ItemId::CHARCOAL, 1)],
+            fluid_outputs: Vec::new(),
+            byproducts: vec![Byproduct { stack: ItemStack::new(ItemId::CHARCOAL, 1), chance: 0.0 }],
+            duration: 4,
+            energy: 40,
+        });
+        // A powered furnace, the shipped one runs unpowered
+        let spec = MachineSpec {
+            energy_capacity: 10_000,
+            ..MachineRegistry::default().get(BlockId::FURNACE).unwrap().clone()
+        };
+        (recipes, items, Machine::new(&spec))
+    }
+
+    #[test]
+    fn test_machine_runs_recipe_to_completion() {
+        let (recipes, items, mut machine) = setup();
+        let mut rng = LootRng::new(3);
+        let log = ItemId::from_block(BlockId::LOG);
+
+        assert_eq!(machine.tick(&recipes, &items, &mut rng), MachineStatus::Idle);
+        machine.input.insert(ItemStack::new(log, 2), &items, Transfer::Execute);
+        assert_eq!(machine.tick(&recipes, &items, &mut rng), MachineStatus::NoPower);
+
+        machine.receive_energy(1_000);
+        for _ in 0..4 {
+            assert_eq!(machine.tick(&recipes, &items, &mut rng), MachineStatus::Working);
+        }
+        assert_eq!(machine.input.count(log), 1);
+        assert_eq!(machine.output.count(ItemId::CHARCOAL), 1);
+        assert_eq!(machine.energy, 1_000 - 40);
+        assert_eq!(machine.progress, 0);
+
+        // Nobody else may fill the outputs
+        let charcoal = ItemStack::new(ItemId::CHARCOAL, 1);
+        assert!(machine.output.insert(charcoal, &items, Transfer::Execute).is_some());
+    }
+
+    #[test]
+    fn test_unpowered_machine_needs_no_energy() {
+        let (recipes, items, _) = setup();
+        let mut machine = Machine::new(MachineRegistry::default().get(BlockId::FURNACE).unwrap());
+        let mut rng = LootRng::new(3);
+        machine.input.insert(ItemStack::new(ItemId::from_block(BlockId::LOG), 1), &items, Transfer::Execute);
+
+        for _ in 0..4 {
+            assert_eq!(machine.tick(&recipes, &items, &mut rng), MachineStatus::Working);
+        }
+        assert_eq!(machine.output.count(ItemId::CHARCOAL), 1);
+        assert_eq!(machine.energy, 0);
+    }
+
+    #[test]
+    fn test_full_output_blocks_machine() {
+        let (recipes, items, mut machine) = setup();
+        let mut rng = LootRng::new(3);
+        machine.receive_energy(1_000);
+        machine.input.insert(ItemStack::new(ItemId::from_block(BlockId::LOG), 1), &items, Transfer::Execute);
+
+        // The second slot must stay free for the possible byproduct
+        machine.output.set(0, Some(ItemStack::new(ItemId::CHARCOAL, 64)));
+        machine.output.set(1, Some(ItemStack::new(ItemId::STONE_AXE, 1)));
+        assert_eq!(machine.tick(&recipes, &items, &mut rng), MachineStatus::Blocked);
+        assert_eq!(machine.energy, 1_000);
+
+        machine.output.extract(1, 1, Transfer::Execute);
+        machine.output.extract(0, 2, Transfer::Execute);
+        assert_eq!(machine.tick(&recipes, &items, &mut rng), MachineStatus::Working);
+    }
+
+    #[test]
+    fn test_progress_survives_save_and_load() {
+        let (recipes, items, mut machine) = setup();
+        let mut rng = LootRng::new(3);
+        machine.receive_energy(1_000);
+        machine.input.insert(ItemStack::new(ItemId::from_block(BlockId::LOG), 1), &items, Transfer::Execute);
+        for _ in 0..3 {
+            machine.tick(&recipes, &items, &mut rng);
+        }
+        assert_eq!(machine.progress, 3);
+
+        let storage = RegionStorage::new(std::env::temp_dir().join(format!("aeternitas_machine_{}", std::process::id())));
+        let local = LocalPos::new(1, 1, 1);
+        let mut chunk = Chunk::empty(ChunkPos::new(0, 0, 0), 0);
+        chunk.set_block(local, BlockId::FURNACE);
+        chunk.block_entities.insert(local, BlockEntity::Machine(Box::new(machine)));
+        storage.save_chunk(&chunk).unwrap();
+        let mut loaded = storage.load_chunk(chunk.pos).unwrap().unwrap();
+        let _ = std::fs::remove_dir_all(storage.root());
+
+        let Some(BlockEntity::Machine(mut machine)) = loaded.block_entities.remove(&local) else {
+            panic!("machine was not loaded");
+        };
+        assert_eq!(machine.recipe, None);
+        // Only the last of the four ticks is left
+        assert_eq!(machine.tick(&recipes, &items, &mut rng), MachineStatus::Working);
+        assert_eq!(machine.output.count(ItemId::CHARCOAL), 1);
+        assert_eq!(machine.progress, 0);
+    }
+
+    #[test]
+    fn test_broken_machine_spills_its_items() {
+        let (_, items, mut machine) = setup();
+        let log = ItemStack::new(ItemId::from_block(BlockId::LOG), 3);
+        let charcoal = ItemStack::new(ItemId::CHARCOAL, 2);
+        machine.input.insert(log, &items, Transfer::Execute);
+        machine.output.set(1, Some(charcoal));
+
+        let mut world = World::new();
+        world.insert_resource(MachineRegistry::default());
+        world.init_resource::<Messages<BlockChanged>>();
+        world.init_resource::<Messages<ItemsDropped>>();
+        let pos = BlockPos::new(1, 1, 1);
+        let mut chunk = Chunk::empty(pos.chunk_pos(), 0);
+        chunk.block_entities.insert(pos.local_pos(), BlockEntity::Machine(Box::new(machine)));
+        let mut manager = ChunkManager::default();
+        manager.register_chunk(pos.chunk_pos(), world.spawn(chunk).id());
+        world.insert_resource(manager);
+
+        world.write_message(BlockChanged {
+            pos,
+            old: BlockId::FURNACE.into(),
+            new: BlockId::AIR.into(),
+            cause: BlockChangeCause::Player,
+        });
+        world.run_system_once(attach_machines).unwrap();
+
+        let spilled: Vec<ItemsDropped> = world.resource_mut::<Messages<ItemsDropped>>().drain().collect();
+        assert_eq!(spilled, vec![ItemsDropped { pos, stacks: vec![log, charcoal] }]);
+        let mut chunks = world.query::<&Chunk>();
+        assert!(chunks.single(&world).unwrap().block_entities.is_empty());
+    }
+
+    #[test]
+    fn test_loaded_machine_blocks_get_machines() {
+        let mut world = World::new();
+        world.insert_resource(MachineRegistry::default());
+
+        // Saved before machines kept their contents, so it has none
+        let (empty, working) = (LocalPos::new(1, 1, 1), LocalPos::new(2, 1, 1));
+        let mut chunk = Chunk::empty(ChunkPos::new(0, 0, 0), 0);
+        chunk.set_block(empty, BlockId::FURNACE);
+        chunk.set_block(working, BlockId::FURNACE);
+        let (.., mut machine) = setup();
+        machine.progress = 3;
+        chunk.block_entities.insert(working, BlockEntity::Machine(Box::new(machine.clone())));
+        let entity = world.spawn(chunk).id();
+
+        world.run_system_once(attach_loaded_machines).unwrap();
+        let chunk = world.entity(entity).get::<Chunk>().unwrap();
+        assert!(matches!(chunk.block_entities.get(&empty), Some(BlockEntity::Machine(_))));
+        assert_eq!(chunk.block_entities.get(&working), Some(&BlockEntity::Machine(Box::new(machine))));
+    }
+}
@@ -0,0 +1,375 @@
+use crate::core::fluid::{FluidId, FluidStack};
+use crate::core::inventory::ItemStack;
+use crate::core::item::{ItemId, ItemRegistry};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Where Recipe Files Are Loaded From
+pub const RECIPE_DIR: &str = "assets/recipes";
+
+/// Kind Of Machine A Recipe Runs In
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum MachineCategory {
+    Smelting,
+    Crushing,
+    Mixing,
+    Assembling,
+}
+
+impl MachineCategory {
+    pub const ALL: [MachineCategory; 4] = [
+        MachineCategory::Smelting,
+        MachineCategory::Crushing,
+        MachineCategory::Mixing,
+        MachineCategory::Assembling,
+    ];
+
+    pub fn index(self) -> u8 {
+        Self::ALL.iter().position(|category| *category == self).unwrap() as u8
+    }
+
+    pub fn from_index(index: u8) -> Option<MachineCategory> {
+        Self::ALL.get(index as usize).copied()
+    }
+}
+
+/// Output Produced With A Probability
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Byproduct {
+    pub stack: ItemStack,
+    pub chance: f32,
+}
+
+/// Processing Recipe
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+    pub id: String,
+    pub category: MachineCategory,
+    pub item_inputs: Vec<ItemStack>,
+    pub fluid_inputs: Vec<FluidStack>,
+    pub item_outputs: Vec<ItemStack>,
+    pub fluid_outputs: Vec<FluidStack>,
+    pub byproducts: Vec<Byproduct>,
+    /// Simulation ticks to finish
+    pub duration: u32,
+    /// Total energy used over the whole duration
+    pub energy: u32,
+}
+
+impl Recipe {
+    /// Energy Drawn Each Tick (rounded up)
+    pub fn energy_per_tick(&self) -> u32 {
+        self.energy.div_ceil(self.duration.max(1))
+    }
+
+    /// Whether The Available Items And Fluids Cover Every Input
+    pub fn matches(&self, items: &[ItemStack], fluids: &[FluidStack]) -> bool {
+        let item_count = |item: ItemId| items.iter().filter(|stack| stack.item == item).map(|stack| stack.count).sum::<u32>();
+        let fluid_amount = |fluid: FluidId| fluids.iter().filter(|stack| stack.fluid == fluid).map(|stack| stack.amount).sum::<u32>();
+
+        self.item_inputs.iter().all(|input| item_count(input.item) >= input.count)
+            && self.fluid_inputs.iter().all(|input| fluid_amount(input.fluid) >= input.amount)
+    }
+}
+
+/// Index Of A Recipe In The Registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecipeId(pub u32);
+
+/// Global Registry for Recipes
+///
+/// Recipes are indexed by category and by each of their inputs, so a
+/// machine only checks recipes that use something it holds.
+#[derive(Resource, Debug, Default)]
+pub struct RecipeRegistry {
+    recipes: Vec<Recipe>,
+    by_id: HashMap<String, RecipeId>,
+    by_item: HashMap<(MachineCategory, ItemId), Vec<RecipeId>>,
+    by_fluid: HashMap<(MachineCategory, FluidId), Vec<RecipeId>>,
+}
+
+impl RecipeRegistry {
+    /// Add A Recipe, Replacing Any With The Same Id
+    pub fn register(&mut self, recipe: Recipe) -> RecipeId {
+        if let Some(&id) = self.by_id.get(&recipe.id) {
+            self.unindex(id);
+            self.recipes[id.0 as usize] = recipe;
+            self.index(id);
+            return id;
+        }
+
+        let id = RecipeId(self.recipes.len() as u32);
+        self.by_id.insert(recipe.id.clone(), id);
+        self.recipes.push(recipe);
+        self.index(id);
+        id
+    }
+
+    fn index(&mut self, id: RecipeId) {
+        let recipe = &self.recipes[id.0 as usize];
+        for input in &recipe.item_inputs {
+            self.by_item.entry((recipe.category, input.item)).or_default().push(id);
+        }
+        for input in &recipe.fluid_inputs {
+            self.by_fluid.entry((recipe.category, input.fluid)).or_default().push(id);
+        }
+    }
+
+    fn unindex(&mut self, id: RecipeId) {
+        for ids in self.by_item.values_mut().chain(self.by_fluid.values_mut()) {
+            ids.retain(|other| *other != id);
+        }
+    }
+
+    pub fn get(&self, id: RecipeId) -> Option<&Recipe> {
+        self.recipes.get(id.0 as usize)
+    }
+
+    pub fn find_id(&self, id: &str) -> Option<RecipeId> {
+        self.by_id.get(id).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.recipes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+
+    /// Recipes Of A Category That Take An Item
+    pub fn using_item(&self, category: MachineCategory, item: ItemId) -> &[RecipeId] {
+        self.by_item.get(&(category, item)).map_or(&[], Vec::as_slice)
+    }
+
+    /// Recipes Of A Category That Take A Fluid
+    pub fn using_fluid(&self, category: MachineCategory, fluid: FluidId) -> &[RecipeId] {
+        self.by_fluid.get(&(category, fluid)).map_or(&[], Vec::as_slice)
+    }
+
+    /// First Recipe Whose Inputs Are All Available (earliest registered wins)
+    pub fn find(&self, category: MachineCategory, items: &[ItemStack], fluids: &[FluidStack]) -> Option<RecipeId> {
+        let mut candidates: Vec<RecipeId> = items
+            .iter()
+            .flat_map(|stack| self.using_item(category, stack.item))
+            .chain(fluids.iter().flat_map(|stack| self.using_fluid(category, stack.fluid)))
+            .copied()
+            .collect();
+        candidates.sort_by_key(|id| id.0);
+        candidates.dedup();
+
+        candidates
+            .into_iter()
+            .find(|id| self.recipes[id.0 as usize].matches(items, fluids))
+    }
+
+    /// Load Every `.ron` File In A Directory (a missing directory holds no recipes)
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>, items: &ItemRegistry) -> io::Result<usize> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .collect();
+        // Later files override earlier ones, so keep the order stable
+        paths.sort();
+
+        let mut loaded = 0;
+        for path in paths {
+            let source = fs::read_to_string(&path)?;
+            let recipes = parse_recipes(&source, items)
+                .map_err(|err| invalid(&format!("{}: {}", path.display(), err)))?;
+            loaded += recipes.len();
+            for recipe in recipes {
+                self.register(recipe);
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Item Amount As Written In Recipe Files
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemDef {
+    item: String,
+    #[serde(default = "one")]
+    count: u32,
+}
+
+/// Byproduct As Written In Recipe Files (the only items with a chance)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ByproductDef {
+    item: String,
+    #[serde(default = "one")]
+    count: u32,
+    #[serde(default = "always")]
+    chance: f32,
+}
+
+/// Fluid Amount As Written In Recipe Files (millibuckets)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FluidDef {
+    fluid: String,
+    amount: u32,
+}
+
+/// Recipe As Written In Recipe Files, Items And Fluids By Name
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeDef {
+    id: String,
+    category: MachineCategory,
+    #[serde(default)]
+    inputs: Vec<ItemDef>,
+    #[serde(default)]
+    fluid_inputs: Vec<FluidDef>,
+    #[serde(default)]
+    outputs: Vec<ItemDef>,
+    #[serde(default)]
+    fluid_outputs: Vec<FluidDef>,
+    #[serde(default)]
+    byproducts: Vec<ByproductDef>,
+    duration: u32,
+    #[serde(default)]
+    energy: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn always() -> f32 {
+    1.0
+}
+
+/// Parse A RON List Of Recipes, Resolving Names Through The Item Registry
+pub fn parse_recipes(source: &str, items: &ItemRegistry) -> io::Result<Vec<Recipe>> {
+    let defs: Vec<RecipeDef> = ron::from_str(source).map_err(|err| invalid(&err.to_string()))?;
+
+    let stack = |name: &str, count: u32| {
+        items
+            .find(name)
+            .map(|item| ItemStack::new(item, count))
+            .ok_or_else(|| invalid(&format!("unknown item {:?}", name)))
+    };
+    let item = |def: &ItemDef| stack(&def.item, def.count);
+    let fluid = |def: &FluidDef| {
+        FluidId::from_name(&def.fluid)
+            .map(|fluid| FluidStack::new(fluid, def.amount))
+            .ok_or_else(|| invalid(&format!("unknown fluid {:?}", def.fluid)))
+    };
+
+    defs.into_iter()
+        .map(|def| {
+            if def.duration == 0 {
+                return Err(invalid(&format!("recipe {:?} has no duration", def.id)));
+            }
+            if def.byproducts.iter().any(|byproduct| !(0.0..=1.0).contains(&byproduct.chance)) {
+                return Err(invalid(&format!("recipe {:?} has a byproduct chance outside 0 to 1", def.id)));
+            }
+            Ok(Recipe {
+                item_inputs: def.inputs.iter().map(item).collect::<io::Result<_>>()?,
+                fluid_inputs: def.fluid_inputs.iter().map(fluid).collect::<io::Result<_>>()?,
+                item_outputs: def.outputs.iter().map(item).collect::<io::Result<_>>()?,
+                fluid_outputs: def.fluid_outputs.iter().map(fluid).collect::<io::Result<_>>()?,
+                byproducts: def
+                    .byproducts
+                    .iter()
+                    .map(|def| Ok(Byproduct { stack: stack(&def.item, def.count)?, chance: def.chance }))
+                    .collect::<io::Result<_>>()?,
+                id: def.id,
+                category: def.category,
+                duration: def.duration,
+                energy: def.energy,
+            })
+        })
+        .collect()
+}
+
+/// Load Recipe Files At Startup
+pub fn load_recipes(mut recipes: ResMut<RecipeRegistry>, items: Res<ItemRegistry>) {
+    match recipes.load_dir(RECIPE_DIR, &items) {
+        Ok(count) => info!("Loaded {} recipes", count),
+        Err(err) => warn!("Failed to load recipes: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::{BlockId, BlockRegistry};
+
+    const SOURCE: &str = r#"[
+        (
+            id: "smelt_charcoal",
+            category: Smelting,
+            inputs: [(item: "Log")],
+            outputs: [(item: "Charcoal")],
+            byproducts: [(item: "Charcoal", chance: 0.1)],
+            duration: 100,
+            energy: 1000,
+        ),
+        (
+            id: "moisten_dirt",
+            category: Mixing,
+            inputs: [(item: "Dirt", count: 2)],
+            fluid_inputs: [(fluid: "Water", amount: 250)],
+            outputs: [(item: "Grass", count: 2)],
+            duration: 40,
+        ),
+    ]"#;
+
+    #[test]
+    fn test_parse_and_lookup_by_input() {
+        let items = ItemRegistry::new(&BlockRegistry::new());
+        let mut registry = RecipeRegistry::default();
+        for recipe in parse_recipes(SOURCE, &items).unwrap() {
+            registry.register(recipe);
+        }
+
+        let log = ItemId::from_block(BlockId::LOG);
+        let dirt = ItemId::from_block(BlockId::DIRT);
+        let smelt = registry.find(MachineCategory::Smelting, &[ItemStack::new(log, 1)], &[]).unwrap();
+        let smelt = registry.get(smelt).unwrap();
+        assert_eq!(smelt.id, "smelt_charcoal");
+        assert_eq!(smelt.energy_per_tick(), 10);
+        assert_eq!(smelt.byproducts[0].chance, 0.1);
+
+        // Wrong category, too little dirt, missing water
+        assert!(registry.find(MachineCategory::Crushing, &[ItemStack::new(log, 1)], &[]).is_none());
+        let water = FluidStack::new(FluidId::WATER, 1000);
+        assert!(registry.find(MachineCategory::Mixing, &[ItemStack::new(dirt, 1)], &[water]).is_none());
+        assert!(registry.find(MachineCategory::Mixing, &[ItemStack::new(dirt, 2)], &[]).is_none());
+        assert!(registry.find(MachineCategory::Mixing, &[ItemStack::new(dirt, 2)], &[water]).is_some());
+        assert_eq!(registry.using_fluid(MachineCategory::Mixing, FluidId::WATER).len(), 1);
+
+        assert!(parse_recipes(&SOURCE.replace("\"Log\"", "\"Nothing\""), &items).is_err());
+        // A chance only means something on byproducts
+        let chanced_output = SOURCE.replace("outputs: [(item: \"Charcoal\")]", "outputs: [(item: \"Charcoal\", chance: 0.5)]");
+        assert!(parse_recipes(&chanced_output, &items).is_err());
+        for chance in ["-0.5", "1.5", "NaN"] {
+            assert!(parse_recipes(&SOURCE.replace("chance: 0.1", &format!("chance: {}", chance)), &items).is_err());
+        }
+        // Misspelled fields must not turn into free recipes
+        assert!(parse_recipes(&SOURCE.replace("energy:", "enrgy:"), &items).is_err());
+        assert!(parse_recipes(&SOURCE.replace("fluid_inputs:", "fluid_input:"), &items).is_err());
+        assert!(parse_recipes(&SOURCE.replace("amount:", "amout:"), &items).is_err());
+        // The shipped recipe files must stay valid
+        assert!(!parse_recipes(include_str!("../../assets/recipes/basic.ron"), &items).unwrap().is_empty());
+    }
+}
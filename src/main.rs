@@ -1,13 +1,32 @@
 use aeternitas::core::block::BlockRegistry;
+use aeternitas::core::inventory::*;
+use aeternitas::core::item::ItemRegistry;
+use aeternitas::core::loot::*;
+use aeternitas::core::tick::*;
+use aeternitas::machine::processing::*;
+use aeternitas::machine::recipe::*;
+use aeternitas::multiblock::entity::*;
+use aeternitas::multiblock::registry::MultiblockRegistry;
 use aeternitas::player::controller::*;
+use aeternitas::player::interaction::*;
+use aeternitas::voxel::meshing::MeshingStrategy;
 use aeternitas::voxel::rendering::*;
 use aeternitas::world::chunk_manager::*;
+use aeternitas::world::events::*;
+use aeternitas::world::generation::*;
+use aeternitas::world::lod::*;
 use aeternitas::world::octree::*;
+use aeternitas::world::pipeline::*;
+use aeternitas::world::region::*;
 use bevy::app::AppExit;
 use bevy::prelude::*;
 use bevy::window::{CursorGrabMode, CursorOptions, WindowResolution};
 
 fn main() {
+    let block_registry = BlockRegistry::new();
+    let item_registry = ItemRegistry::new(&block_registry);
+    let loot_tables = LootTables::new(&block_registry, &item_registry);
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -19,25 +38,87 @@ fn main() {
         }))
         // Resources
         .init_resource::<ChunkManager>()
-        .insert_resource(BlockRegistry::new())
+        .insert_resource(block_registry)
+        .insert_resource(item_registry)
+        .insert_resource(loot_tables)
+        .init_resource::<LootRng>()
+        .init_resource::<RecipeRegistry>()
+        .init_resource::<MachineRegistry>()
+        .init_resource::<MultiblockRegistry>()
+        .init_resource::<RegionStorage>()
+        .init_resource::<WorldSeed>()
+        .init_resource::<ActiveGenerator>()
+        .init_resource::<GenerationPipeline>()
+        .init_resource::<LodCache>()
+        .init_resource::<TargetedBlock>()
+        .init_resource::<MiningProgress>()
+        .init_resource::<SelectedBlock>()
+        .init_resource::<ChunkMeshTasks>()
+        .init_resource::<MeshingStrategy>()
+        .init_resource::<SimulationClock>()
+        // Messages
+        .add_message::<BlockChanged>()
+        .add_message::<NeighborUpdate>()
+        .add_message::<BlockBroken>()
+        .add_message::<ItemsDropped>()
+        .add_message::<InventoryChanged>()
         // Startup
         .add_systems(
             Startup,
-            (setup_camera, setup_lighting, cursor, setup_octree),
+            (setup_camera, setup_lighting, cursor, setup_octree, load_recipes),
         )
-        // Update
+        // Player
         .add_systems(
             Update,
             (
-                camera_movement,
+                toggle_noclip,
+                camera_movement.after(toggle_noclip),
+                walk_movement.after(toggle_noclip),
                 camera_look,
+                update_targeted_block.after(camera_movement).after(walk_movement).after(camera_look),
+                mine_targeted_block.after(update_targeted_block),
+                place_selected_block.after(update_targeted_block),
+                drop_block_loot.after(mine_targeted_block),
+                send_inventory_changes.after(drop_block_loot),
+            ),
+        )
+        // World
+        .add_systems(
+            Update,
+            (
                 update_chunks_around_player,
+                merge_lod_chunks.after(update_chunks_around_player).before(spawn_generated_chunks),
+                spawn_generated_chunks.after(update_chunks_around_player),
                 //mark_initial_chunks, // only used for test chunks
                 mark_dirty_chunks,
+                remesh_border_neighbors.before(mesh_chunks),
                 mesh_chunks,
+                upload_chunk_meshes.after(mesh_chunks),
+                save_modified_chunks,
                 exit_system,
+                save_chunks_on_exit.after(exit_system),
+                invalidate_edited_lods,
+                notify_block_neighbors,
+                attach_machines.after(notify_block_neighbors),
+                attach_loaded_machines.after(spawn_generated_chunks),
+                update_multiblocks.after(attach_machines),
+                validate_loaded_multiblocks.after(spawn_generated_chunks),
+                flush_stale_lods_on_exit.after(exit_system),
             ),
         )
+        // Simulation
+        .configure_sets(
+            SimulationTick,
+            (
+                SimulationSet::RandomTicks,
+                SimulationSet::Fluids,
+                SimulationSet::Transport,
+                SimulationSet::Machines,
+            )
+                .chain(),
+        )
+        .add_systems(Update, (simulation_controls, run_simulation.after(simulation_controls)))
+        .add_systems(SimulationTick, process_machines.in_set(SimulationSet::Machines))
         .run();
 }
 
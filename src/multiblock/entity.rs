@@ -0,0 +1,388 @@
+use crate::core::block::{BlockRegistry, Direction};
+use crate::core::position::BlockPos;
+use crate::multiblock::registry::{MultiblockPattern, MultiblockRegistry, Orientation};
+use crate::world::chunk::{BlockEntity, Chunk};
+use crate::world::events::BlockChanged;
+use crate::world::voxel_world::VoxelWorld;
+use bevy::prelude::*;
+
+/// Pattern Name And Orientation Of The Structure Formed At A Controller
+fn formed_at(voxels: &VoxelWorld, controller: BlockPos) -> Option<(String, Orientation)> {
+    match voxels.block_entity(controller)? {
+        BlockEntity::MultiblockController { pattern, orientation } => Some((pattern.clone(), *orientation)),
+        _ => None,
+    }
+}
+
+fn is_part_of(entity: Option<&BlockEntity>, controller: BlockPos) -> bool {
+    matches!(entity, Some(BlockEntity::MultiblockPart { controller: other, .. }) if *other == controller)
+}
+
+/// Whether The Structure At A Controller Includes A Position
+fn claims(voxels: &VoxelWorld, registry: &MultiblockRegistry, controller: BlockPos, pos: BlockPos) -> bool {
+    let Some((name, orientation)) = formed_at(voxels, controller) else {
+        return false;
+    };
+    registry
+        .get(&name)
+        .is_some_and(|pattern| pattern.placed_cells(controller, orientation).any(|(cell, _)| cell == pos))
+}
+
+/// Form The First Pattern Of A Controller That Matches, Returning Whether One Did
+///
+/// Every cell must be loaded, so structures only form while all of their
+/// chunks are present. Cells holding another block entity (including parts
+/// of other structures) are never taken over.
+pub fn try_form(voxels: &mut VoxelWorld, registry: &MultiblockRegistry, blocks: &BlockRegistry, controller: BlockPos) -> bool {
+    if formed_at(voxels, controller).is_some() {
+        return false;
+    }
+    let Some(state) = voxels.get_state(controller) else {
+        return false;
+    };
+    let facing = blocks.facing(state).unwrap_or(Direction::North);
+
+    for pattern in registry.for_controller(state.block()) {
+        for orientation in pattern.orientations(facing) {
+            if !pattern.matches(controller, orientation, blocks, |pos| voxels.get_state(pos)) {
+                continue;
+            }
+            let taken = pattern
+                .placed_cells(controller, orientation)
+                .any(|(pos, _)| voxels.block_entity(pos).is_some());
+            if taken {
+                continue;
+            }
+
+            form(voxels, pattern, controller, orientation);
+            return true;
+        }
+    }
+    false
+}
+
+fn form(voxels: &mut VoxelWorld, pattern: &MultiblockPattern, controller: BlockPos, orientation: Orientation) {
+    for (pos, cell) in pattern.placed_cells(controller, orientation) {
+        voxels.set_block_entity(pos, Some(BlockEntity::MultiblockPart { controller, role: cell.role }));
+    }
+    voxels.set_block_entity(
+        controller,
+        Some(BlockEntity::MultiblockController {
+            pattern: pattern.name.clone(),
+            orientation,
+        }),
+    );
+    info!("Formed {} at {:?}", pattern.name, controller);
+}
+
+/// Tear Down The Structure At A Controller, Returning Whether One Was Formed
+///
+/// Parts in unloaded chunks keep pointing at the controller until their
+/// chunk loads again, see `validate_loaded_multiblocks`.
+pub fn unform(voxels: &mut VoxelWorld, registry: &MultiblockRegistry, controller: BlockPos) -> bool {
+    let Some((name, orientation)) = formed_at(voxels, controller) else {
+        return false;
+    };
+
+    voxels.set_block_entity(controller, None);
+    if let Some(pattern) = registry.get(&name) {
+        for (pos, _) in pattern.placed_cells(controller, orientation) {
+            if is_part_of(voxels.block_entity(pos), controller) {
+                voxels.set_block_entity(pos, None);
+            }
+        }
+    }
+    info!("Unformed {} at {:?}", name, controller);
+    true
+}
+
+/// Whether A Formed Structure Still Holds Together (cells in unloaded chunks are trusted)
+fn still_valid(
+    voxels: &VoxelWorld,
+    registry: &MultiblockRegistry,
+    blocks: &BlockRegistry,
+    controller: BlockPos,
+    name: &str,
+    orientation: Orientation,
+) -> bool {
+    let Some(pattern) = registry.get(name) else {
+        return false;
+    };
+
+    voxels.get_block(controller) == Some(pattern.controller)
+        && pattern.placed_cells(controller, orientation).all(|(pos, cell)| match voxels.get_state(pos) {
+            Some(state) => cell.matcher.matches(state, blocks) && is_part_of(voxels.block_entity(pos), controller),
+            None => true,
+        })
+}
+
+/// Form And Unform Structures As Blocks Change
+///
+/// Changing any part, or the controller itself, unforms the structure.
+/// Runs after `attach_machines`, which may already have replaced a part
+/// entity, so nearby structures claiming a changed cell are unformed too.
+/// Every controller within reach of a change then tries to form, so
+/// placing the last missing block completes a structure.
+pub fn update_multiblocks(
+    // VoxelWorld writes BlockChanged, so the reader cannot sit next to it
+    mut params: ParamSet<(MessageReader<BlockChanged>, VoxelWorld)>,
+    registry: Res<MultiblockRegistry>,
+    blocks: Res<BlockRegistry>,
+) {
+    let changes: Vec<BlockChanged> = params
+        .p0()
+        .read()
+        .filter(|change| change.old.block() != change.new.block())
+        .copied()
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
+
+    let mut voxels = params.p1();
+    let reach = registry.reach();
+    let mut candidates = Vec::new();
+
+    for change in changes {
+        match voxels.block_entity(change.pos) {
+            Some(BlockEntity::MultiblockPart { controller, .. }) => {
+                let controller = *controller;
+                if !unform(&mut voxels, &registry, controller) {
+                    // Controller unloaded (or stale part), it notices the gap once loaded
+                    voxels.set_block_entity(change.pos, None);
+                }
+            }
+            Some(BlockEntity::MultiblockController { .. }) => {
+                unform(&mut voxels, &registry, change.pos);
+            }
+            _ => {}
+        }
+
+        for y in -reach..=reach {
+            for z in -reach..=reach {
+                for x in -reach..=reach {
+                    let pos = change.pos + IVec3::new(x, y, z);
+                    let is_controller = voxels
+                        .get_block(pos)
+                        .and_then(|block| blocks.get(block))
+                        .is_some_and(|props| props.is_multiblock_controller);
+                    if !is_controller {
+                        continue;
+                    }
+                    if claims(&voxels, &registry, pos, change.pos) {
+                        unform(&mut voxels, &registry, pos);
+                    }
+                    if !candidates.contains(&pos) {
+                        candidates.push(pos);
+                    }
+                }
+            }
+        }
+    }
+
+    for controller in candidates {
+        try_form(&mut voxels, &registry, &blocks, controller);
+    }
+}
+
+/// Check Structures Touching Newly Loaded Chunks
+///
+/// Edits only happen in loaded chunks, so a structure may have been
+/// unformed while some of its parts were unloaded, or lost a part while
+/// its controller was unloaded. Parts whose controller no longer claims
+/// them are dropped, and controllers whose loaded cells no longer match
+/// are unformed, then formed again if a part was replaced meanwhile.
+pub fn validate_loaded_multiblocks(
+    mut params: ParamSet<(Query<&Chunk, Added<Chunk>>, VoxelWorld)>,
+    registry: Res<MultiblockRegistry>,
+    blocks: Res<BlockRegistry>,
+) {
+    let mut parts = Vec::new();
+    let mut controllers = Vec::new();
+    for chunk in params.p0().iter().filter(|chunk| chunk.depth == 0) {
+        let origin = chunk.pos.to_world_pos();
+        for (local, entity) in &chunk.block_entities {
+            let pos = origin + IVec3::new(local.x as i32, local.y as i32, local.z as i32);
+            match entity {
+                BlockEntity::MultiblockPart { controller, .. } => parts.push((pos, *controller)),
+                BlockEntity::MultiblockController { pattern, orientation } => {
+                    controllers.push((pos, pattern.clone(), *orientation));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut voxels = params.p1();
+    for (controller, name, orientation) in controllers {
+        if !still_valid(&voxels, &registry, &blocks, controller, &name, orientation) {
+            unform(&mut voxels, &registry, controller);
+            try_form(&mut voxels, &registry, &blocks, controller);
+        }
+    }
+
+    for (pos, controller) in parts {
+        // Unloaded controllers are checked when they load
+        if voxels.is_loaded(controller) && !claims(&voxels, &registry, controller, pos) {
+            voxels.set_block_entity(pos, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockId;
+    use crate::core::position::ChunkPos;
+    use crate::machine::processing::{attach_machines, MachineRegistry};
+    use crate::multiblock::registry::BlockMatcher;
+    use crate::world::chunk_manager::ChunkManager;
+    use crate::world::events::{BlockChangeCause, ItemsDropped};
+    use bevy::ecs::system::RunSystemOnce;
+
+    /// Front row in the controller's chunk, the rest behind it in the next one
+    const CONTROLLER: BlockPos = BlockPos { x: 1, y: 5, z: 0 };
+    const BACK: ChunkPos = ChunkPos { x: 0, y: 0, z: -1 };
+
+    fn setup() -> World {
+        let mut world = World::new();
+        world.init_resource::<Messages<BlockChanged>>();
+        world.init_resource::<Messages<ItemsDropped>>();
+        world.insert_resource(BlockRegistry::new());
+        world.init_resource::<MultiblockRegistry>();
+        world.init_resource::<MachineRegistry>();
+
+        let mut manager = ChunkManager::default();
+        for pos in [ChunkPos::new(0, 0, 0), BACK] {
+            manager.register_chunk(pos, world.spawn(Chunk::empty(pos, 0)).id());
+        }
+        world.insert_resource(manager);
+
+        world
+            .run_system_once(|mut voxels: VoxelWorld, registry: Res<MultiblockRegistry>| {
+                let pattern = registry.get("blast_furnace").unwrap();
+                for (pos, cell) in pattern.placed_cells(CONTROLLER, Orientation::new(Direction::North, false)) {
+                    let block = match &cell.matcher {
+                        BlockMatcher::Block(block) => *block,
+                        BlockMatcher::Tag(_) => BlockId::MACHINE_CASING,
+                        BlockMatcher::Air => BlockId::AIR,
+                    };
+                    voxels.set_block(pos, block, BlockChangeCause::Player);
+                }
+                // Default state faces north
+                voxels.set_block(CONTROLLER, BlockId::BLAST_FURNACE, BlockChangeCause::Player);
+            })
+            .unwrap();
+        process(&mut world);
+        world
+    }
+
+    fn process(world: &mut World) {
+        // Same order as the app
+        world.run_system_once(attach_machines).unwrap();
+        world.run_system_once(update_multiblocks).unwrap();
+        world.resource_mut::<Messages<BlockChanged>>().clear();
+    }
+
+    fn set_block(world: &mut World, pos: BlockPos, block: BlockId) {
+        world
+            .run_system_once(move |mut voxels: VoxelWorld| {
+                voxels.set_block(pos, block, BlockChangeCause::Player);
+            })
+            .unwrap();
+        process(world);
+    }
+
+    fn unload(world: &mut World, pos: ChunkPos) -> Chunk {
+        let entity = world.resource::<ChunkManager>().get_chunk_entity(pos).unwrap();
+        let chunk = world.entity(entity).get::<Chunk>().unwrap().clone();
+        world.despawn(entity);
+        world.resource_mut::<ChunkManager>().unregister_chunk(pos);
+        chunk
+    }
+
+    fn load(world: &mut World, chunk: Chunk) {
+        let pos = chunk.pos;
+        let entity = world.spawn(chunk).id();
+        world.resource_mut::<ChunkManager>().register_chunk(pos, entity);
+        world.run_system_once(validate_loaded_multiblocks).unwrap();
+    }
+
+    /// Parts Pointing At The Controller, And Whether It Is Formed
+    fn structure(world: &mut World) -> (usize, bool) {
+        let mut chunks = world.query::<&Chunk>();
+        let entities = chunks.iter(world).flat_map(|chunk| chunk.block_entities.values());
+        let mut parts = 0;
+        let mut formed = false;
+        for entity in entities {
+            match entity {
+                BlockEntity::MultiblockPart { controller, .. } if *controller == CONTROLLER => parts += 1,
+                BlockEntity::MultiblockController { .. } => formed = true,
+                _ => {}
+            }
+        }
+        (parts, formed)
+    }
+
+    #[test]
+    fn test_form_and_unform_across_chunks() {
+        let mut world = setup();
+        assert_eq!(structure(&mut world), (26, true));
+
+        // Breaking a casing in the back chunk unforms everything
+        let casing = CONTROLLER + IVec3::new(0, 0, -2);
+        set_block(&mut world, casing, BlockId::AIR);
+        assert_eq!(structure(&mut world), (0, false));
+
+        // Putting it back completes the structure again
+        set_block(&mut world, casing, BlockId::MACHINE_CASING);
+        assert_eq!(structure(&mut world), (26, true));
+
+        // So does breaking the controller
+        set_block(&mut world, CONTROLLER, BlockId::AIR);
+        assert_eq!(structure(&mut world), (0, false));
+    }
+
+    #[test]
+    fn test_machine_in_a_part_cell_unforms() {
+        let mut world = setup();
+
+        // The machine replaces the part entity before the structure sees the change
+        let casing = CONTROLLER + IVec3::new(0, 0, -2);
+        set_block(&mut world, casing, BlockId::FURNACE);
+        assert_eq!(structure(&mut world), (0, false));
+
+        // Its machine is gone again before the structure tries to form
+        set_block(&mut world, casing, BlockId::MACHINE_CASING);
+        assert_eq!(structure(&mut world), (26, true));
+    }
+
+    #[test]
+    fn test_unloaded_chunks_are_reconciled_on_load() {
+        let mut world = setup();
+
+        // Controller broken while the back chunk is away, its parts go stale
+        let back = unload(&mut world, BACK);
+        set_block(&mut world, CONTROLLER, BlockId::AIR);
+        load(&mut world, back);
+        assert_eq!(structure(&mut world), (0, false));
+
+        // Part broken while the controller is away
+        set_block(&mut world, CONTROLLER, BlockId::BLAST_FURNACE);
+        assert_eq!(structure(&mut world), (26, true));
+        let casing = CONTROLLER + IVec3::new(0, 0, -2);
+        let front = unload(&mut world, ChunkPos::new(0, 0, 0));
+        set_block(&mut world, casing, BlockId::AIR);
+        load(&mut world, front);
+        assert_eq!(structure(&mut world), (0, false));
+
+        // Part broken and put back while the controller is away
+        set_block(&mut world, casing, BlockId::MACHINE_CASING);
+        assert_eq!(structure(&mut world), (26, true));
+        let front = unload(&mut world, ChunkPos::new(0, 0, 0));
+        set_block(&mut world, casing, BlockId::AIR);
+        set_block(&mut world, casing, BlockId::MACHINE_CASING);
+        load(&mut world, front);
+        assert_eq!(structure(&mut world), (26, true));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod entity;
+pub mod registry;
\ No newline at end of file
@@ -0,0 +1,345 @@
+use crate::core::block::{BlockId, BlockRegistry, BlockState, Direction};
+use crate::core::position::BlockPos;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// What A Pattern Cell Accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockMatcher {
+    Block(BlockId),
+    /// Any block carrying the tag (lowercase)
+    Tag(String),
+    Air,
+}
+
+impl BlockMatcher {
+    pub fn tag(tag: &str) -> Self {
+        BlockMatcher::Tag(tag.to_lowercase())
+    }
+
+    pub fn matches(&self, state: BlockState, blocks: &BlockRegistry) -> bool {
+        match self {
+            BlockMatcher::Block(block) => state.block() == *block,
+            BlockMatcher::Tag(tag) => blocks.has_tag(state.block(), tag),
+            BlockMatcher::Air => state.block() == BlockId::AIR,
+        }
+    }
+}
+
+/// Job Of A Cell In A Formed Structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotRole {
+    InputHatch,
+    OutputHatch,
+    EnergyPort,
+}
+
+impl SlotRole {
+    pub const ALL: [SlotRole; 3] = [SlotRole::InputHatch, SlotRole::OutputHatch, SlotRole::EnergyPort];
+
+    pub fn index(self) -> u8 {
+        Self::ALL.iter().position(|role| *role == self).unwrap() as u8
+    }
+
+    pub fn from_index(index: u8) -> Option<SlotRole> {
+        Self::ALL.get(index as usize).copied()
+    }
+}
+
+/// One Cell Of A Pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternCell {
+    /// Offset from the controller, as if it faced north
+    pub offset: IVec3,
+    pub matcher: BlockMatcher,
+    pub role: Option<SlotRole>,
+}
+
+/// Meaning Of A Character In Pattern Layers
+#[derive(Debug, Clone)]
+pub struct PatternKey {
+    pub symbol: char,
+    pub matcher: BlockMatcher,
+    pub role: Option<SlotRole>,
+}
+
+impl PatternKey {
+    pub fn new(symbol: char, matcher: BlockMatcher) -> Self {
+        Self { symbol, matcher, role: None }
+    }
+
+    pub fn with_role(self, role: SlotRole) -> Self {
+        Self { role: Some(role), ..self }
+    }
+}
+
+/// How A Pattern Sits In The World
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    /// Facing of the controller
+    pub facing: Direction,
+    /// West and east swapped before rotating
+    pub mirrored: bool,
+}
+
+impl Orientation {
+    pub fn new(facing: Direction, mirrored: bool) -> Self {
+        Self { facing, mirrored }
+    }
+
+    /// Pattern Offset To World Offset
+    pub fn apply(self, offset: IVec3) -> IVec3 {
+        let x = if self.mirrored { -offset.x } else { offset.x };
+        let (y, z) = (offset.y, offset.z);
+        match self.facing {
+            Direction::North => IVec3::new(x, y, z),
+            Direction::South => IVec3::new(-x, y, -z),
+            Direction::East => IVec3::new(z, y, -x),
+            Direction::West => IVec3::new(-z, y, x),
+            // Tilted so the pattern's top points away from the front
+            Direction::Up => IVec3::new(x, z, -y),
+            Direction::Down => IVec3::new(x, -z, y),
+        }
+    }
+}
+
+/// Multiblock Structure Pattern
+///
+/// Cells are stored relative to the controller as if it faced north, with
+/// the structure usually built behind it. The controller's facing in the
+/// world rotates the whole pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiblockPattern {
+    pub name: String,
+    pub controller: BlockId,
+    /// Every cell except the controller
+    pub cells: Vec<PatternCell>,
+    /// Whether the mirrored layout forms as well
+    pub mirrorable: bool,
+}
+
+impl MultiblockPattern {
+    /// Pattern From Horizontal Layers
+    ///
+    /// Layers go bottom to top, rows from the front (controller side) to the
+    /// back and characters from west to east, all with the controller facing
+    /// north. `C` marks the controller and spaces match anything.
+    pub fn from_layers(name: &str, controller: BlockId, layers: &[&[&str]], keys: &[PatternKey]) -> Result<Self, String> {
+        let mut origin = None;
+        let mut cells = Vec::new();
+
+        for (y, layer) in layers.iter().enumerate() {
+            for (row, line) in layer.iter().enumerate() {
+                for (x, symbol) in line.chars().enumerate() {
+                    let offset = IVec3::new(x as i32, y as i32, -(row as i32));
+                    match symbol {
+                        ' ' => {}
+                        'C' if origin.is_some() => return Err(format!("{name}: more than one controller")),
+                        'C' => origin = Some(offset),
+                        _ => {
+                            let key = keys
+                                .iter()
+                                .find(|key| key.symbol == symbol)
+                                .ok_or_else(|| format!("{name}: unknown symbol {symbol:?}"))?;
+                            cells.push(PatternCell { offset, matcher: key.matcher.clone(), role: key.role });
+                        }
+                    }
+                }
+            }
+        }
+
+        let origin = origin.ok_or_else(|| format!("{name}: no controller"))?;
+        for cell in &mut cells {
+            cell.offset -= origin;
+        }
+        Ok(Self {
+            name: name.to_string(),
+            controller,
+            cells,
+            mirrorable: false,
+        })
+    }
+
+    pub fn mirrorable(self) -> Self {
+        Self { mirrorable: true, ..self }
+    }
+
+    /// Orientations Worth Checking For A Controller Facing
+    pub fn orientations(&self, facing: Direction) -> Vec<Orientation> {
+        let mut orientations = vec![Orientation::new(facing, false)];
+        if self.mirrorable {
+            orientations.push(Orientation::new(facing, true));
+        }
+        orientations
+    }
+
+    /// Cells Placed Around A Controller, In World Positions
+    pub fn placed_cells(&self, controller: BlockPos, orientation: Orientation) -> impl Iterator<Item = (BlockPos, &PatternCell)> {
+        self.cells
+            .iter()
+            .map(move |cell| (controller + orientation.apply(cell.offset), cell))
+    }
+
+    /// Whether Every Cell Matches (blocks reading as None, e.g. unloaded, never match)
+    pub fn matches(
+        &self,
+        controller: BlockPos,
+        orientation: Orientation,
+        blocks: &BlockRegistry,
+        mut get_state: impl FnMut(BlockPos) -> Option<BlockState>,
+    ) -> bool {
+        self.placed_cells(controller, orientation)
+            .all(|(pos, cell)| get_state(pos).is_some_and(|state| cell.matcher.matches(state, blocks)))
+    }
+}
+
+/// Global Registry for Multiblock Patterns
+#[derive(Resource, Debug, Clone)]
+pub struct MultiblockRegistry {
+    patterns: Vec<MultiblockPattern>,
+    by_name: HashMap<String, usize>,
+    by_controller: HashMap<BlockId, Vec<usize>>,
+}
+
+impl Default for MultiblockRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register_default_patterns();
+        registry
+    }
+}
+
+impl MultiblockRegistry {
+    pub fn empty() -> Self {
+        Self {
+            patterns: Vec::new(),
+            by_name: HashMap::new(),
+            by_controller: HashMap::new(),
+        }
+    }
+
+    /// Add A Pattern, Replacing Any With The Same Name
+    pub fn register(&mut self, pattern: MultiblockPattern) {
+        if let Some(&index) = self.by_name.get(&pattern.name) {
+            let old = std::mem::replace(&mut self.patterns[index], pattern);
+            if let Some(indices) = self.by_controller.get_mut(&old.controller) {
+                indices.retain(|other| *other != index);
+            }
+            let controller = self.patterns[index].controller;
+            self.by_controller.entry(controller).or_default().push(index);
+            return;
+        }
+
+        let index = self.patterns.len();
+        self.by_name.insert(pattern.name.clone(), index);
+        self.by_controller.entry(pattern.controller).or_default().push(index);
+        self.patterns.push(pattern);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MultiblockPattern> {
+        self.by_name.get(name).map(|index| &self.patterns[*index])
+    }
+
+    /// Patterns A Controller Block Can Form
+    pub fn for_controller(&self, block: BlockId) -> impl Iterator<Item = &MultiblockPattern> {
+        self.by_controller
+            .get(&block)
+            .into_iter()
+            .flatten()
+            .map(|index| &self.patterns[*index])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MultiblockPattern> {
+        self.patterns.iter()
+    }
+
+    /// Farthest Any Cell Sits From Its Controller Along One Axis
+    pub fn reach(&self) -> i32 {
+        self.patterns
+            .iter()
+            .flat_map(|pattern| &pattern.cells)
+            .map(|cell| cell.offset.abs().max_element())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn register_default_patterns(&mut self) {
+        let keys = [
+            PatternKey::new('c', BlockMatcher::tag("casing")),
+            PatternKey::new('-', BlockMatcher::Air),
+            PatternKey::new('I', BlockMatcher::Block(BlockId::INPUT_HATCH)).with_role(SlotRole::InputHatch),
+            PatternKey::new('O', BlockMatcher::Block(BlockId::OUTPUT_HATCH)).with_role(SlotRole::OutputHatch),
+            PatternKey::new('E', BlockMatcher::Block(BlockId::ENERGY_PORT)).with_role(SlotRole::EnergyPort),
+        ];
+
+        // Blast Furnace (3x3x3, hollow, hatches at the back)
+        let blast_furnace = MultiblockPattern::from_layers(
+            "blast_furnace",
+            BlockId::BLAST_FURNACE,
+            &[
+                &["ccc", "ccc", "cEc"],
+                &["cCc", "c-c", "IcO"],
+                &["ccc", "ccc", "ccc"],
+            ],
+            &keys,
+        )
+        .expect("blast furnace pattern is valid");
+        self.register(blast_furnace.mirrorable());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orientation_rotates_and_mirrors() {
+        let behind_left = IVec3::new(-1, 0, -1);
+        assert_eq!(Orientation::new(Direction::North, false).apply(behind_left), behind_left);
+        assert_eq!(Orientation::new(Direction::South, false).apply(behind_left), IVec3::new(1, 0, 1));
+        assert_eq!(Orientation::new(Direction::East, false).apply(behind_left), IVec3::new(-1, 0, 1));
+        assert_eq!(Orientation::new(Direction::West, false).apply(behind_left), IVec3::new(1, 0, -1));
+        assert_eq!(Orientation::new(Direction::Up, false).apply(behind_left), IVec3::new(-1, -1, 0));
+        assert_eq!(Orientation::new(Direction::North, true).apply(behind_left), IVec3::new(1, 0, -1));
+
+        // The front always ends up on the facing side
+        for facing in Direction::ALL {
+            assert_eq!(Orientation::new(facing, false).apply(IVec3::Z), facing.offset());
+        }
+    }
+
+    #[test]
+    fn test_blast_furnace_pattern() {
+        let blocks = BlockRegistry::new();
+        let registry = MultiblockRegistry::default();
+        let pattern = registry.for_controller(BlockId::BLAST_FURNACE).next().unwrap();
+        assert_eq!(pattern.cells.len(), 26);
+        let input = pattern.cells.iter().find(|cell| cell.role == Some(SlotRole::InputHatch)).unwrap();
+        assert_eq!(input.offset, IVec3::new(-1, 0, -2));
+
+        // Build it facing east, then break one casing
+        let controller = BlockPos::new(10, 5, 10);
+        let orientation = Orientation::new(Direction::East, false);
+        let mut world: HashMap<BlockPos, BlockState> = pattern
+            .placed_cells(controller, orientation)
+            .map(|(pos, cell)| {
+                let block = match &cell.matcher {
+                    BlockMatcher::Block(block) => *block,
+                    BlockMatcher::Tag(_) => BlockId::MACHINE_CASING,
+                    BlockMatcher::Air => BlockId::AIR,
+                };
+                (pos, block.into())
+            })
+            .collect();
+        let matches = |world: &HashMap<BlockPos, BlockState>, orientation| {
+            pattern.matches(controller, orientation, &blocks, |pos| world.get(&pos).copied())
+        };
+
+        assert!(matches(&world, orientation));
+        assert!(!matches(&world, Orientation::new(Direction::West, false)));
+        assert!(!matches(&world, Orientation::new(Direction::East, true)));
+
+        world.insert(controller + IVec3::new(0, 1, 0), BlockId::STONE.into());
+        assert!(!matches(&world, orientation));
+    }
+}
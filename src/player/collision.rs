@@ -0,0 +1,209 @@
+use bevy::prelude::*;
+
+/// Gap Kept Between A Box And The Voxels It Touches
+const SKIN: f32 = 1e-4;
+
+/// Axis Aligned Bounding Box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn translated(self, offset: Vec3) -> Self {
+        Self::new(self.min + offset, self.max + offset)
+    }
+
+    /// Voxel Cells Overlapped Along One Axis (inclusive, touching does not count)
+    fn cells(self, axis: usize) -> (i32, i32) {
+        (
+            (self.min[axis] + SKIN).floor() as i32,
+            (self.max[axis] - SKIN).floor() as i32,
+        )
+    }
+}
+
+/// Result Of Moving A Box Through The Voxel Grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionResult {
+    /// Motion actually applied
+    pub motion: Vec3,
+    /// Axes on which the requested motion was cut short
+    pub blocked: BVec3,
+}
+
+impl CollisionResult {
+    /// Standing on something after moving down
+    pub fn landed(&self, requested: Vec3) -> bool {
+        self.blocked.y && requested.y < 0.0
+    }
+}
+
+/// Sweep A Box Along One Axis, Stopping At The First Solid Voxel
+///
+/// Voxels the box already overlaps are ignored, so a box stuck inside
+/// terrain can always move out of it.
+pub fn sweep_axis(aabb: Aabb, axis: usize, distance: f32, is_solid: &impl Fn(IVec3) -> bool) -> f32 {
+    if distance == 0.0 {
+        return 0.0;
+    }
+
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let (u_min, u_max) = aabb.cells(u);
+    let (v_min, v_max) = aabb.cells(v);
+    let layer_solid = |layer: i32| {
+        (u_min..=u_max).any(|a| {
+            (v_min..=v_max).any(|b| {
+                let mut cell = IVec3::ZERO;
+                cell[axis] = layer;
+                cell[u] = a;
+                cell[v] = b;
+                is_solid(cell)
+            })
+        })
+    };
+
+    if distance > 0.0 {
+        let front = aabb.max[axis];
+        let mut layer = (front - SKIN).floor() as i32 + 1;
+        while (layer as f32) < front + distance {
+            if layer_solid(layer) {
+                return (layer as f32 - front - SKIN).clamp(0.0, distance);
+            }
+            layer += 1;
+        }
+    } else {
+        let front = aabb.min[axis];
+        let mut layer = (front + SKIN).floor() as i32 - 1;
+        while (layer + 1) as f32 > front + distance {
+            if layer_solid(layer) {
+                return ((layer + 1) as f32 - front + SKIN).clamp(distance, 0.0);
+            }
+            layer -= 1;
+        }
+    }
+    distance
+}
+
+/// Move A Box, Resolving Collisions One Axis At A Time (y, then x, then z)
+pub fn move_aabb(aabb: Aabb, motion: Vec3, is_solid: &impl Fn(IVec3) -> bool) -> CollisionResult {
+    let mut moved = aabb;
+    let mut applied = Vec3::ZERO;
+    let mut blocked = [false; 3];
+
+    for axis in [1, 0, 2] {
+        let distance = sweep_axis(moved, axis, motion[axis], is_solid);
+        blocked[axis] = distance != motion[axis];
+        applied[axis] = distance;
+        let mut offset = Vec3::ZERO;
+        offset[axis] = distance;
+        moved = moved.translated(offset);
+    }
+
+    CollisionResult { motion: applied, blocked: BVec3::from_array(blocked) }
+}
+
+/// Move A Grounded Box, Stepping Up Ledges Of At Most `step_height`
+///
+/// The step is taken only if it lets the box travel further horizontally
+/// than sliding along the obstacle would.
+pub fn move_with_step(
+    aabb: Aabb,
+    motion: Vec3,
+    step_height: f32,
+    on_ground: bool,
+    is_solid: &impl Fn(IVec3) -> bool,
+) -> CollisionResult {
+    let direct = move_aabb(aabb, motion, is_solid);
+    if !on_ground || step_height <= 0.0 || !(direct.blocked.x || direct.blocked.z) {
+        return direct;
+    }
+
+    // Lift, move horizontally, then settle back down
+    let lift = sweep_axis(aabb, 1, step_height, is_solid);
+    let raised = aabb.translated(Vec3::Y * lift);
+    let horizontal = move_aabb(raised, Vec3::new(motion.x, 0.0, motion.z), is_solid);
+    let shifted = raised.translated(horizontal.motion);
+    let settle = sweep_axis(shifted, 1, -lift + motion.y.min(0.0), is_solid);
+
+    let stepped = Vec3::new(horizontal.motion.x, lift + settle, horizontal.motion.z);
+    if stepped.xz().length_squared() <= direct.motion.xz().length_squared() {
+        return direct;
+    }
+
+    CollisionResult {
+        motion: stepped,
+        blocked: BVec3::new(horizontal.blocked.x, direct.blocked.y, horizontal.blocked.z),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Floor at y = 0 plus extra cells
+    fn world(extra: &[IVec3]) -> impl Fn(IVec3) -> bool + use<> {
+        let cells: HashSet<IVec3> = extra.iter().copied().collect();
+        move |cell: IVec3| cell.y == 0 || cells.contains(&cell)
+    }
+
+    fn player_at(feet: Vec3) -> Aabb {
+        Aabb::new(feet - Vec3::new(0.3, 0.0, 0.3), feet + Vec3::new(0.3, 1.8, 0.3))
+    }
+
+    #[test]
+    fn test_falling_box_lands_on_floor() {
+        let solid = world(&[]);
+        let aabb = player_at(Vec3::new(0.5, 3.0, 0.5));
+
+        let result = move_aabb(aabb, Vec3::new(0.0, -10.0, 0.0), &solid);
+        assert!(result.landed(Vec3::new(0.0, -10.0, 0.0)));
+        assert!((aabb.min.y + result.motion.y - 1.0).abs() < 1e-3);
+
+        // Resting on the floor, gravity does not sink it
+        let resting = aabb.translated(result.motion);
+        let again = move_aabb(resting, Vec3::new(0.0, -0.5, 0.0), &solid);
+        assert!(again.blocked.y);
+        assert!(again.motion.y.abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_wall_stops_horizontal_motion() {
+        let solid = world(&[IVec3::new(2, 1, 0), IVec3::new(2, 2, 0)]);
+        let aabb = player_at(Vec3::new(0.5, 1.0, 0.5));
+
+        let result = move_aabb(aabb, Vec3::new(5.0, 0.0, 0.0), &solid);
+        assert!(result.blocked.x);
+        assert!((aabb.max.x + result.motion.x - 2.0).abs() < 1e-3);
+
+        // Large steps cannot tunnel through a thin wall
+        let far = move_aabb(aabb, Vec3::new(50.0, 0.0, 0.0), &solid);
+        assert_eq!(far.motion, result.motion);
+    }
+
+    #[test]
+    fn test_step_up_single_block_only() {
+        let ledge = world(&[IVec3::new(2, 1, 0)]);
+        let aabb = player_at(Vec3::new(0.5, 1.0, 0.5));
+        let motion = Vec3::new(2.0, -0.1, 0.0);
+
+        let stepped = move_with_step(aabb, motion, 1.0, true, &ledge);
+        assert!(!stepped.blocked.x);
+        assert!((stepped.motion.y - 1.0).abs() < 1e-3);
+
+        // Airborne boxes do not step
+        assert!(move_with_step(aabb, motion, 1.0, false, &ledge).blocked.x);
+
+        // Two blocks high is a wall
+        let wall = world(&[IVec3::new(2, 1, 0), IVec3::new(2, 2, 0)]);
+        let blocked = move_with_step(aabb, motion, 1.0, true, &wall);
+        assert!(blocked.blocked.x);
+        assert!(blocked.motion.y.abs() < 1e-3);
+    }
+}
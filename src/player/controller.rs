@@ -1,3 +1,9 @@
+use crate::core::block::BlockRegistry;
+use crate::core::inventory::{Inventory, PLAYER_INVENTORY_SIZE};
+use crate::core::position::BlockPos;
+use crate::player::collision::{move_with_step, Aabb};
+use crate::player::interaction::HeldTool;
+use crate::world::voxel_world::VoxelWorld;
 use bevy::prelude::*;
 use bevy::input::mouse::MouseMotion;
 use bevy::window::{CursorGrabMode, PrimaryWindow, WindowMode}; //Not used due to API problems
@@ -22,6 +28,72 @@ impl Default for FlyCamera {
     }
 }
 
+/// Player Collision Box (relative to the eyes)
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PlayerCollider {
+    pub width: f32,
+    pub height: f32,
+    pub eye_height: f32,
+}
+
+impl Default for PlayerCollider {
+    fn default() -> Self {
+        Self {
+            width: 0.6,
+            height: 1.8,
+            eye_height: 1.62,
+        }
+    }
+}
+
+impl PlayerCollider {
+    /// World Space Box For An Eye Position
+    pub fn aabb(&self, eye: Vec3) -> Aabb {
+        let half = self.width / 2.0;
+        let feet = eye - Vec3::Y * self.eye_height;
+        Aabb::new(
+            feet - Vec3::new(half, 0.0, half),
+            feet + Vec3::new(half, self.height, half),
+        )
+    }
+
+    /// Whether A Block Cell Intersects The Box
+    pub fn overlaps_block(&self, eye: Vec3, block_min: Vec3) -> bool {
+        let aabb = self.aabb(eye);
+        let block_max = block_min + Vec3::ONE;
+        aabb.min.cmplt(block_max).all() && aabb.max.cmpgt(block_min).all()
+    }
+}
+
+/// Walking Movement (FlyCamera handles movement while `noclip` is on)
+#[derive(Component, Debug, Clone)]
+pub struct PlayerPhysics {
+    pub walk_speed: f32,
+    pub jump_speed: f32,
+    pub gravity: f32,
+    pub terminal_velocity: f32,
+    /// Tallest ledge walked onto without jumping
+    pub step_height: f32,
+    pub velocity: Vec3,
+    pub on_ground: bool,
+    pub noclip: bool,
+}
+
+impl Default for PlayerPhysics {
+    fn default() -> Self {
+        Self {
+            walk_speed: 4.3,
+            jump_speed: 8.0,
+            gravity: 28.0,
+            terminal_velocity: 60.0,
+            step_height: 1.0,
+            velocity: Vec3::ZERO,
+            on_ground: false,
+            noclip: true,
+        }
+    }
+}
+
 /// Camera
 pub fn setup_camera(mut commands: Commands) {
     let yaw = 0.0;
@@ -39,6 +111,10 @@ pub fn setup_camera(mut commands: Commands) {
             pitch,
             ..Default::default()
         },
+        HeldTool::default(),
+        PlayerCollider::default(),
+        PlayerPhysics::default(),
+        Inventory::new(PLAYER_INVENTORY_SIZE),
     ));
 }
 
@@ -46,9 +122,13 @@ pub fn setup_camera(mut commands: Commands) {
 pub fn camera_movement(
     time: Res<Time>,
     keys: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(&mut Transform, &FlyCamera)>,
+    mut query: Query<(&mut Transform, &FlyCamera, Option<&PlayerPhysics>)>,
 ) {
-    for (mut transform, camera) in query.iter_mut() {
+    for (mut transform, camera, physics) in query.iter_mut() {
+        if physics.is_some_and(|physics| !physics.noclip) {
+            continue;
+        }
+
         let mut velocity = Vec3::ZERO;
         
         // Movement Normalization
@@ -90,6 +170,82 @@ pub fn camera_movement(
     }
 }
 
+/// Toggle Between Flying And Walking
+pub fn toggle_noclip(keys: Res<ButtonInput<KeyCode>>, mut query: Query<&mut PlayerPhysics>) {
+    if !keys.just_pressed(KeyCode::KeyN) {
+        return;
+    }
+
+    for mut physics in query.iter_mut() {
+        physics.noclip = !physics.noclip;
+        physics.velocity = Vec3::ZERO;
+        physics.on_ground = false;
+        info!("Noclip {}", if physics.noclip { "on" } else { "off" });
+    }
+}
+
+/// Walking Movement
+///
+/// Unloaded blocks count as solid, so the player never falls out of the
+/// loaded world. Nothing moves until the chunk around the player loads.
+pub fn walk_movement(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    registry: Res<BlockRegistry>,
+    voxels: VoxelWorld,
+    mut query: Query<(&mut Transform, &PlayerCollider, &mut PlayerPhysics)>,
+) {
+    let dt = time.delta_secs();
+    let is_solid = |cell: IVec3| {
+        voxels
+            .get_block(BlockPos::new(cell.x, cell.y, cell.z))
+            .is_none_or(|block| registry.get_or_air(block).is_solid)
+    };
+
+    for (mut transform, collider, mut physics) in query.iter_mut() {
+        let eye = transform.translation;
+        if physics.noclip || !voxels.is_loaded(BlockPos::new(eye.x.floor() as i32, eye.y.floor() as i32, eye.z.floor() as i32)) {
+            continue;
+        }
+
+        let forward = Vec3::new(transform.forward().x, 0.0, transform.forward().z).normalize_or_zero();
+        let right = Vec3::new(transform.right().x, 0.0, transform.right().z).normalize_or_zero();
+        let mut wish = Vec3::ZERO;
+        if keys.pressed(KeyCode::KeyW) {
+            wish += forward;
+        }
+        if keys.pressed(KeyCode::KeyS) {
+            wish -= forward;
+        }
+        if keys.pressed(KeyCode::KeyA) {
+            wish -= right;
+        }
+        if keys.pressed(KeyCode::KeyD) {
+            wish += right;
+        }
+
+        // Sprint
+        let speed_multiplier = if keys.pressed(KeyCode::ControlLeft) { 1.5 } else { 1.0 };
+        let horizontal = wish.normalize_or_zero() * physics.walk_speed * speed_multiplier;
+        physics.velocity.x = horizontal.x;
+        physics.velocity.z = horizontal.z;
+
+        if physics.on_ground && keys.pressed(KeyCode::Space) {
+            physics.velocity.y = physics.jump_speed;
+        }
+        physics.velocity.y = (physics.velocity.y - physics.gravity * dt).max(-physics.terminal_velocity);
+
+        let motion = physics.velocity * dt;
+        let result = move_with_step(collider.aabb(eye), motion, physics.step_height, physics.on_ground, &is_solid);
+        transform.translation += result.motion;
+
+        physics.on_ground = result.landed(motion);
+        if result.blocked.y {
+            physics.velocity.y = 0.0;
+        }
+    }
+}
+
 /// Mouse Look
 pub fn camera_look(
     mut mouse_motion: MessageReader<MouseMotion>,
@@ -0,0 +1,505 @@
+use crate::core::block::{BlockId, BlockProperties, BlockRegistry, Direction, ToolType};
+use crate::core::inventory::{Inventory, ItemStack, Transfer};
+use crate::core::item::ItemRegistry;
+use crate::core::loot::{LootRng, LootTables};
+use crate::core::position::{BlockPos, MAX_BLOCK_Y, MIN_BLOCK_Y};
+use crate::player::controller::{FlyCamera, PlayerCollider};
+use crate::world::events::{BlockChangeCause, ItemsDropped};
+use crate::world::voxel_world::VoxelWorld;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// How Far The Player Can Reach (blocks)
+pub const REACH: f32 = 8.0;
+
+/// Block Hit By A Ray
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    pub pos: BlockPos,
+    /// Face of the block the ray entered through
+    pub face: Direction,
+    pub distance: f32,
+    pub block: BlockId,
+}
+
+/// Block The Player Is Looking At
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct TargetedBlock(pub Option<RaycastHit>);
+
+/// Tool The Player Is Holding
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct HeldTool {
+    pub tool_type: ToolType,
+    /// Mining speed multiplier when the tool matches the block
+    pub speed: f32,
+}
+
+impl Default for HeldTool {
+    /// Bare hands
+    fn default() -> Self {
+        Self {
+            tool_type: ToolType::None,
+            speed: 1.0,
+        }
+    }
+}
+
+impl HeldTool {
+    /// Whether Breaking The Block With This Tool Yields Drops
+    pub fn can_harvest(&self, props: &BlockProperties) -> bool {
+        props.required_tool.is_none_or(|required| required == self.tool_type)
+    }
+
+    /// Seconds Needed To Break A Block (None if unbreakable)
+    pub fn mining_time(&self, props: &BlockProperties) -> Option<f32> {
+        if props.hardness < 0.0 {
+            return None;
+        }
+
+        let speed = if props.tool_type != ToolType::None && props.tool_type == self.tool_type {
+            self.speed
+        } else {
+            1.0
+        };
+        // Mining without the required tool is slow, too
+        let penalty = if self.can_harvest(props) { 1.5 } else { 5.0 };
+        Some(props.hardness * penalty / speed)
+    }
+}
+
+/// Held-Button Mining State
+#[derive(Resource, Debug, Default)]
+pub struct MiningProgress {
+    pub target: Option<(BlockPos, BlockId)>,
+    pub elapsed: f32,
+}
+
+impl MiningProgress {
+    pub fn reset(&mut self) {
+        self.target = None;
+        self.elapsed = 0.0;
+    }
+}
+
+/// Sent When The Player Finishes Breaking A Block
+#[derive(Message, Debug, Clone, Copy, PartialEq)]
+pub struct BlockBroken {
+    pub pos: BlockPos,
+    pub block: BlockId,
+    pub tool: ToolType,
+    /// False when the block needed a tool the player lacked, so nothing drops
+    pub harvested: bool,
+}
+
+/// Items Lying In The World
+#[derive(Component, Debug, Clone, Copy)]
+pub struct DroppedItem(pub ItemStack);
+
+/// Block Placed On Right Click
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedBlock(pub BlockId);
+
+impl Default for SelectedBlock {
+    fn default() -> Self {
+        Self(BlockId::STONE)
+    }
+}
+
+/// Voxel DDA Raycast
+///
+/// Steps through every block the ray touches, in order, until it finds one
+/// that `is_solid`. `get_block` returns `None` for unloaded blocks, which
+/// stop the ray. Coordinates wrap horizontally through `BlockPos`.
+pub fn raycast(
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+    registry: &BlockRegistry,
+    get_block: impl Fn(BlockPos) -> Option<BlockId>,
+) -> Option<RaycastHit> {
+    let dir = direction.normalize_or_zero();
+    if dir == Vec3::ZERO {
+        return None;
+    }
+
+    let mut cell = origin.floor().as_ivec3();
+    let step = IVec3::new(
+        if dir.x > 0.0 { 1 } else { -1 },
+        if dir.y > 0.0 { 1 } else { -1 },
+        if dir.z > 0.0 { 1 } else { -1 },
+    );
+
+    // Ray length to cross one block, and to reach the next boundary, per axis
+    let t_delta = dir.abs().recip();
+    let mut t_max = Vec3::ZERO;
+    for axis in 0..3 {
+        t_max[axis] = if dir[axis] > 0.0 {
+            (cell[axis] as f32 + 1.0 - origin[axis]) / dir[axis]
+        } else if dir[axis] < 0.0 {
+            (origin[axis] - cell[axis] as f32) / -dir[axis]
+        } else {
+            f32::INFINITY
+        };
+    }
+
+    // Starting inside a block counts as hitting the face facing the ray
+    let mut face = entry_face(dir.abs().max_position(), step);
+    let mut distance = 0.0;
+
+    loop {
+        let pos = BlockPos::new(cell.x, cell.y, cell.z);
+        let block = get_block(pos)?;
+        if registry.get_or_air(block).is_solid {
+            return Some(RaycastHit { pos, face, distance, block });
+        }
+
+        let axis = t_max.min_position();
+        if t_max[axis] > max_distance {
+            return None;
+        }
+
+        distance = t_max[axis];
+        cell[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+        face = entry_face(axis, step);
+
+        // Past the top or bottom of the world
+        if !(MIN_BLOCK_Y..=MAX_BLOCK_Y).contains(&cell.y) {
+            return None;
+        }
+    }
+}
+
+/// Face Entered When Stepping Along An Axis
+fn entry_face(axis: usize, step: IVec3) -> Direction {
+    match (axis, step[axis] > 0) {
+        (0, true) => Direction::West,
+        (0, false) => Direction::East,
+        (1, true) => Direction::Down,
+        (1, false) => Direction::Up,
+        (_, true) => Direction::South,
+        (_, false) => Direction::North,
+    }
+}
+
+/// Update The Targeted Block From The Camera
+pub fn update_targeted_block(
+    camera_query: Query<&Transform, With<FlyCamera>>,
+    voxels: VoxelWorld,
+    registry: Res<BlockRegistry>,
+    mut target: ResMut<TargetedBlock>,
+) {
+    let Ok(transform) = camera_query.single() else {
+        return;
+    };
+
+    let hit = raycast(
+        transform.translation,
+        *transform.forward(),
+        REACH,
+        &registry,
+        |pos| voxels.get_block(pos),
+    );
+
+    // Only touch the resource when the target changed
+    target.set_if_neq(TargetedBlock(hit));
+}
+
+/// Mouse Buttons Aimed At The Targeted Block
+#[derive(SystemParam)]
+pub struct BlockInput<'w> {
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    target: Res<'w, TargetedBlock>,
+}
+
+impl BlockInput<'_> {
+    /// Targeted Block While A Button Is Held
+    pub fn held(&self, button: MouseButton) -> Option<RaycastHit> {
+        self.target.0.filter(|_| self.mouse.pressed(button))
+    }
+
+    /// Targeted Block On The Frame A Button Goes Down
+    pub fn just_pressed(&self, button: MouseButton) -> Option<RaycastHit> {
+        self.target.0.filter(|_| self.mouse.just_pressed(button))
+    }
+}
+
+/// Mine The Targeted Block While The Left Button Is Held
+pub fn mine_targeted_block(
+    time: Res<Time>,
+    input: BlockInput,
+    registry: Res<BlockRegistry>,
+    player_query: Query<Option<&HeldTool>, With<FlyCamera>>,
+    mut progress: ResMut<MiningProgress>,
+    mut voxels: VoxelWorld,
+    mut broken: MessageWriter<BlockBroken>,
+) {
+    let Some(hit) = input.held(MouseButton::Left) else {
+        progress.reset();
+        return;
+    };
+
+    // Looking at a different block starts over
+    if progress.target != Some((hit.pos, hit.block)) {
+        progress.target = Some((hit.pos, hit.block));
+        progress.elapsed = 0.0;
+    }
+
+    let tool = player_query.single().ok().flatten().copied().unwrap_or_default();
+    let props = registry.get_or_air(hit.block);
+    let Some(mining_time) = tool.mining_time(props) else {
+        return;
+    };
+
+    progress.elapsed += time.delta_secs();
+    if progress.elapsed < mining_time {
+        return;
+    }
+
+    if voxels.set_block(hit.pos, BlockId::AIR, BlockChangeCause::Player).is_some() {
+        broken.write(BlockBroken {
+            pos: hit.pos,
+            block: hit.block,
+            tool: tool.tool_type,
+            harvested: tool.can_harvest(props),
+        });
+        info!("Broke {} at {:?}", props.name, hit.pos);
+    }
+    progress.reset();
+}
+
+/// Place The Selected Block Against The Targeted Face
+pub fn place_selected_block(
+    input: BlockInput,
+    selected: Res<SelectedBlock>,
+    registry: Res<BlockRegistry>,
+    player_query: Query<(&Transform, &PlayerCollider), With<FlyCamera>>,
+    mut voxels: VoxelWorld,
+) {
+    let (Some(hit), Ok((transform, collider))) = (input.just_pressed(MouseButton::Right), player_query.single()) else {
+        return;
+    };
+
+    let pos = hit.pos + hit.face.offset();
+    // Only replace empty space in loaded chunks
+    if voxels.get_block(pos).is_none_or(|block| registry.get_or_air(block).is_solid) {
+        return;
+    }
+
+    if registry.get_or_air(selected.0).is_solid
+        && collider.overlaps_block(transform.translation, pos.to_vec3())
+    {
+        return;
+    }
+
+    // Fronts face the player, like a furnace placed in front of you
+    let facing = Direction::from_look(*transform.forward()).opposite();
+    let state = registry.with_facing(selected.0.into(), facing);
+    voxels.set_state(pos, state, BlockChangeCause::Player);
+}
+
+/// Shared Look Of Dropped Items
+#[derive(SystemParam)]
+pub struct ItemVisuals<'w, 's> {
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    handles: Local<'s, Option<(Handle<Mesh>, Handle<StandardMaterial>)>>,
+}
+
+impl ItemVisuals<'_, '_> {
+    /// Mesh And Material, Created On First Use
+    pub fn get(&mut self) -> (Handle<Mesh>, Handle<StandardMaterial>) {
+        let (meshes, materials) = (&mut self.meshes, &mut self.materials);
+        self.handles
+            .get_or_insert_with(|| {
+                (
+                    meshes.add(Cuboid::from_length(0.25)),
+                    materials.add(StandardMaterial::from_color(Color::srgb(0.9, 0.8, 0.3))),
+                )
+            })
+            .clone()
+    }
+}
+
+/// Items Coming Out Of Blocks This Frame
+#[derive(SystemParam)]
+pub struct BlockDrops<'w, 's> {
+    broken: MessageReader<'w, 's, BlockBroken>,
+    spilled: MessageReader<'w, 's, ItemsDropped>,
+    loot_tables: Res<'w, LootTables>,
+    rng: ResMut<'w, LootRng>,
+}
+
+impl BlockDrops<'_, '_> {
+    /// Rolled Loot Of Broken Blocks, Then Spilled Contents
+    pub fn read(&mut self) -> Vec<(BlockPos, ItemStack)> {
+        let mut drops = Vec::new();
+        for event in self.broken.read() {
+            if let Some(table) = self.loot_tables.get(event.block) {
+                let stacks = table.roll(event.tool, event.harvested, &mut self.rng);
+                drops.extend(stacks.into_iter().map(|stack| (event.pos, stack)));
+            }
+        }
+        for event in self.spilled.read() {
+            drops.extend(event.stacks.iter().map(|stack| (event.pos, *stack)));
+        }
+        drops
+    }
+}
+
+/// Hand Out Loot For Broken Blocks, And Whatever They Held
+///
+/// Drops go straight into the player's inventory. Whatever does not fit
+/// is spawned as item entities at the block.
+pub fn drop_block_loot(
+    mut commands: Commands,
+    mut drops: BlockDrops,
+    items: Res<ItemRegistry>,
+    mut player_query: Query<&mut Inventory, With<FlyCamera>>,
+    mut visuals: ItemVisuals,
+) {
+    for (pos, stack) in drops.read() {
+        let left = match player_query.single_mut() {
+            Ok(mut inventory) => inventory.insert(stack, &items, Transfer::Execute),
+            Err(_) => Some(stack),
+        };
+        let Some(left) = left else {
+            continue;
+        };
+
+        let (mesh, material) = visuals.get();
+        commands.spawn((
+            DroppedItem(left),
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::from_translation(pos.to_vec3() + Vec3::splat(0.5)),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockProperties;
+    use crate::core::position::ChunkPos;
+    use crate::world::chunk::Chunk;
+    use std::collections::HashMap;
+
+    struct TestWorld {
+        chunks: HashMap<ChunkPos, Chunk>,
+    }
+
+    impl TestWorld {
+        fn new(chunks: impl IntoIterator<Item = Chunk>) -> Self {
+            Self {
+                chunks: chunks.into_iter().map(|chunk| (chunk.pos, chunk)).collect(),
+            }
+        }
+
+        fn set(&mut self, pos: BlockPos, block: BlockId) {
+            self.chunks.get_mut(&pos.chunk_pos()).unwrap().set_block(pos.local_pos(), block);
+        }
+
+        fn get(&self, pos: BlockPos) -> Option<BlockId> {
+            self.chunks.get(&pos.chunk_pos()).map(|chunk| chunk.get_block(pos.local_pos()))
+        }
+    }
+
+    #[test]
+    fn test_ray_hits_ground_from_above() {
+        let registry = BlockRegistry::new();
+        let world = TestWorld::new([Chunk::test_chunk(ChunkPos::new(0, 0, 0), 0)]);
+
+        let hit = raycast(Vec3::new(4.5, 10.5, 4.5), Vec3::NEG_Y, 10.0, &registry, |pos| world.get(pos)).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(4, 6, 4));
+        assert_eq!(hit.face, Direction::Up);
+        assert_eq!(hit.block, BlockId::GRASS);
+        assert!((hit.distance - 3.5).abs() < 1e-5);
+
+        // Out of reach
+        assert!(raycast(Vec3::new(4.5, 10.5, 4.5), Vec3::NEG_Y, 3.0, &registry, |pos| world.get(pos)).is_none());
+    }
+
+    #[test]
+    fn test_ray_crosses_chunks_and_skips_non_solid() {
+        let mut registry = BlockRegistry::new();
+        let glass_like = BlockId(100);
+        registry.register(BlockProperties {
+            id: glass_like,
+            name: "Decoration".to_string(),
+            is_solid: false,
+            ..Default::default()
+        });
+
+        let mut world = TestWorld::new([
+            Chunk::empty(ChunkPos::new(0, 0, 0), 0),
+            Chunk::empty(ChunkPos::new(1, 0, 0), 0),
+        ]);
+        world.set(BlockPos::new(20, 2, 3), glass_like);
+        world.set(BlockPos::new(40, 2, 3), BlockId::STONE);
+
+        let hit = raycast(Vec3::new(10.5, 2.5, 3.5), Vec3::X, 40.0, &registry, |pos| world.get(pos)).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(40, 2, 3));
+        assert_eq!(hit.face, Direction::West);
+        assert!((hit.distance - 29.5).abs() < 1e-4);
+
+        // Unloaded chunks stop the ray
+        assert!(raycast(Vec3::new(10.5, 2.5, 3.5), Vec3::NEG_X, 40.0, &registry, |pos| world.get(pos)).is_none());
+    }
+
+    #[test]
+    fn test_ray_wraps_horizontally() {
+        let registry = BlockRegistry::new();
+        let east_edge = BlockPos::new(511, 0, 0).chunk_pos();
+        let west_edge = BlockPos::new(-512, 0, 0).chunk_pos();
+        let mut world = TestWorld::new([Chunk::empty(east_edge, 0), Chunk::empty(west_edge, 0)]);
+        world.set(BlockPos::new(-511, 0, 0), BlockId::STONE);
+
+        // Walking east off the edge arrives on the west side of the world
+        let hit = raycast(Vec3::new(510.5, 0.5, 0.5), Vec3::X, 10.0, &registry, |pos| world.get(pos)).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(-511, 0, 0));
+        assert_eq!(hit.face, Direction::West);
+        assert!((hit.distance - 2.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_mining_time_depends_on_tool() {
+        let registry = BlockRegistry::new();
+        let stone = registry.get_or_air(BlockId::STONE);
+        let dirt = registry.get_or_air(BlockId::DIRT);
+
+        let hands = HeldTool::default();
+        let pickaxe = HeldTool { tool_type: ToolType::Pickaxe, speed: 4.0 };
+
+        // Stone needs a pickaxe to drop anything
+        assert!(!hands.can_harvest(stone));
+        assert!(pickaxe.can_harvest(stone));
+        assert!(hands.can_harvest(dirt));
+
+        let bare = hands.mining_time(stone).unwrap();
+        let tooled = pickaxe.mining_time(stone).unwrap();
+        assert!((bare - 1.5 * 5.0).abs() < 1e-5);
+        assert!((tooled - 1.5 * 1.5 / 4.0).abs() < 1e-5);
+
+        // The wrong tool is no faster than bare hands
+        assert_eq!(pickaxe.mining_time(dirt), hands.mining_time(dirt));
+
+        let bedrock = BlockProperties { hardness: -1.0, ..stone.clone() };
+        assert!(pickaxe.mining_time(&bedrock).is_none());
+    }
+
+    #[test]
+    fn test_placement_overlap_and_facing() {
+        let collider = PlayerCollider::default();
+        let eye = Vec3::new(0.5, 11.62, 0.5);
+
+        // Feet at y = 10, head up to y = 11.8
+        assert!(collider.overlaps_block(eye, Vec3::new(0.0, 10.0, 0.0)));
+        assert!(collider.overlaps_block(eye, Vec3::new(0.0, 11.0, 0.0)));
+        assert!(!collider.overlaps_block(eye, Vec3::new(0.0, 9.0, 0.0)));
+        assert!(!collider.overlaps_block(eye, Vec3::new(1.0, 10.0, 0.0)));
+
+        assert_eq!(Direction::from_look(Vec3::new(0.2, -0.9, 0.8)), Direction::North);
+        assert_eq!(Direction::from_look(Vec3::new(-0.7, 0.0, 0.3)).opposite(), Direction::East);
+    }
+}
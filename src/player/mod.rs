@@ -1,5 +1,5 @@
+pub mod collision;
 pub mod controller;
+pub mod interaction;
 
-pub use controller::{setup_camera, camera_movement, camera_look, FlyCamera};
-
-// Interaction module will be added later
\ No newline at end of file
+pub use controller::{setup_camera, camera_movement, camera_look, FlyCamera};
\ No newline at end of file
@@ -1,170 +1,421 @@
-use crate::core::{block::{BlockId, BlockRegistry}, position::{LocalPos, CHUNK_SIZE}};
+use crate::core::{block::{BlockId, BlockRegistry, BlockState, Direction}, position::{ChunkPos, LocalPos, CHUNK_SIZE}};
 use crate::world::chunk::Chunk;
+use crate::world::chunk_manager::touching_positions;
 use bevy::prelude::*;
 
-/// Mesh Generation (Not Optimized)
-pub fn generate_chunk_mesh(chunk: &Chunk, block_registry: &BlockRegistry) -> Mesh {
-    let mut positions = Vec::new();
-    let mut normals = Vec::new();
-    let mut uvs = Vec::new();
-    let mut colors = Vec::new();
-    let mut indices = Vec::new();
+const BORDER_AREA: usize = CHUNK_SIZE as usize * CHUNK_SIZE as usize;
+
+/// Face Layer Of A Neighbor Chunk Touching This Chunk
+#[derive(Debug, Clone)]
+pub enum BorderSlice {
+    Uniform(BlockId),
+    Blocks(Box<[BlockId; BORDER_AREA]>),
+}
+
+impl BorderSlice {
+    /// Indexed by the two in-plane axes, in x/y/z order
+    pub fn get(&self, a: u8, b: u8) -> BlockId {
+        match self {
+            Self::Uniform(block) => *block,
+            Self::Blocks(blocks) => blocks[b as usize * CHUNK_SIZE as usize + a as usize],
+        }
+    }
+}
+
+/// Unwrapped Block Coordinates Of A Chunk Corner
+fn block_origin(pos: ChunkPos) -> [i32; 3] {
+    [pos.x, pos.y, pos.z].map(|v| v * CHUNK_SIZE as i32)
+}
+
+/// Neighbor Border Slices In `ChunkPos::neighbors` Order (+X, -X, +Y, -Y, +Z, -Z)
+#[derive(Debug, Clone, Default)]
+pub struct NeighborBorders(pub [Option<BorderSlice>; 6]);
+
+impl NeighborBorders {
+    /// Copy The Touching Layer Of Every Neighbor
+    ///
+    /// `lookup` returns the loaded chunk covering a chunk position at any
+    /// LOD. A face is only hidden when the neighbor fully covers it: one
+    /// coarser solid block, or every finer block in front of it.
+    pub fn capture<'a>(chunk: &Chunk, lookup: impl Fn(ChunkPos) -> Option<&'a Chunk>) -> Self {
+        let mut borders = Self::default();
+
+        for (face, border) in borders.0.iter_mut().enumerate() {
+            let mut neighbors: Vec<&Chunk> = Vec::new();
+            for pos in touching_positions(chunk.pos, chunk.depth, face) {
+                // Clamped world edges fold back onto this chunk
+                if let Some(neighbor) = lookup(pos)
+                    && neighbor.pos != chunk.pos
+                    && !neighbors.iter().any(|known| known.pos == neighbor.pos)
+                {
+                    neighbors.push(neighbor);
+                }
+            }
+
+            *border = match neighbors.as_slice() {
+                [] => None,
+                [neighbor] if neighbor.depth == chunk.depth && neighbor.uniform_block().is_some() => {
+                    neighbor.uniform_block().map(BorderSlice::Uniform)
+                }
+                _ => Some(Self::slice(chunk, face, &neighbors)),
+            };
+        }
+
+        borders
+    }
+
+    fn slice(chunk: &Chunk, face: usize, neighbors: &[&Chunk]) -> BorderSlice {
+        let size = CHUNK_SIZE as i32;
+        let axis = face / 2;
+        let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+        // Keep the in-plane axes in x/y/z order
+        let (u, v) = (u.min(v), u.max(v));
+        // The neighbor on +X touches with its x = 0 layer, and so on
+        let layer = if face.is_multiple_of(2) { 0 } else { CHUNK_SIZE - 1 };
+
+        let scale = 1i32 << chunk.depth;
+        let origin = block_origin(chunk.pos);
+        let extent = size * scale;
+
+        let mut blocks = Box::new([BlockId::AIR; BORDER_AREA]);
+        let mut covered = vec![0i32; BORDER_AREA];
+
+        for neighbor in neighbors {
+            let neighbor_scale = 1i32 << neighbor.depth;
+            let neighbor_origin = block_origin(neighbor.pos);
+            let overlap = scale.min(neighbor_scale);
+
+            for b in 0..CHUNK_SIZE {
+                for a in 0..CHUNK_SIZE {
+                    let mut local = [layer; 3];
+                    local[u] = a;
+                    local[v] = b;
+                    let block = neighbor.get_block(LocalPos::new(local[0], local[1], local[2]));
+                    if block == BlockId::AIR {
+                        continue;
+                    }
+
+                    // Block extent on this face, in blocks relative to this chunk
+                    let start_u = neighbor_origin[u] + a as i32 * neighbor_scale - origin[u];
+                    let start_v = neighbor_origin[v] + b as i32 * neighbor_scale - origin[v];
+                    if start_u >= extent || start_v >= extent
+                        || start_u + neighbor_scale <= 0 || start_v + neighbor_scale <= 0
+                    {
+                        continue;
+                    }
+
+                    let cells_u = start_u.max(0) / scale..((start_u + neighbor_scale).min(extent) + scale - 1) / scale;
+                    let cells_v = start_v.max(0) / scale..((start_v + neighbor_scale).min(extent) + scale - 1) / scale;
+                    for cell_v in cells_v {
+                        for cell_u in cells_u.clone() {
+                            let index = (cell_v * size + cell_u) as usize;
+                            covered[index] += overlap * overlap;
+                            blocks[index] = block;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Partially covered faces stay visible
+        for (block, area) in blocks.iter_mut().zip(covered) {
+            if area < scale * scale {
+                *block = BlockId::AIR;
+            }
+        }
+        BorderSlice::Blocks(blocks)
+    }
+
+    /// Block Just Outside The Chunk (None if the neighbor is unknown)
+    fn outside(&self, x: i32, y: i32, z: i32) -> Option<BlockId> {
+        let size = CHUNK_SIZE as i32;
+        let (index, a, b) = if x >= size {
+            (0, y, z)
+        } else if x < 0 {
+            (1, y, z)
+        } else if y >= size {
+            (2, x, z)
+        } else if y < 0 {
+            (3, x, z)
+        } else if z >= size {
+            (4, x, y)
+        } else {
+            (5, x, y)
+        };
+        self.0[index].as_ref().map(|slice| slice.get(a as u8, b as u8))
+    }
+}
+
+/// Vertex Buffers For One Chunk
+#[derive(Default)]
+struct MeshBuffers {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuffers {
+    fn push_quad(&mut self, verts: [[f32; 3]; 4], normal: [f32; 3], color: [f32; 4]) {
+        let base_idx = self.positions.len() as u32;
+
+        for vert in verts {
+            self.positions.push(vert);
+            self.normals.push(normal);
+            self.uvs.push([0.0, 0.0]);
+            self.colors.push(color);
+        }
+
+        self.indices.extend_from_slice(&[
+            base_idx, base_idx + 2, base_idx + 1,
+            base_idx, base_idx + 3, base_idx + 2,
+        ]);
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(
+            bevy::mesh::PrimitiveTopology::TriangleList,
+            default(),
+        );
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh.insert_indices(bevy::mesh::Indices::U32(self.indices));
+
+        mesh
+    }
+}
+
+/// Chunk Meshing Algorithm
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeshingStrategy {
+    /// One quad per visible block face
+    Naive,
+    /// Merge coplanar faces of the same block into larger quads
+    #[default]
+    Greedy,
+}
+
+/// Block Faces In Meshing Order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Face {
+    Up,
+    Down,
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Face {
+    const ALL: [Face; 6] = [Face::Up, Face::Down, Face::North, Face::South, Face::East, Face::West];
+
+    fn offset(self) -> [i32; 3] {
+        match self {
+            Face::Up => [0, 1, 0],
+            Face::Down => [0, -1, 0],
+            Face::North => [0, 0, 1],
+            Face::South => [0, 0, -1],
+            Face::East => [1, 0, 0],
+            Face::West => [-1, 0, 0],
+        }
+    }
+
+    fn normal(self) -> [f32; 3] {
+        self.offset().map(|v| v as f32)
+    }
+
+    fn direction(self) -> Direction {
+        match self {
+            Face::Up => Direction::Up,
+            Face::Down => Direction::Down,
+            Face::North => Direction::North,
+            Face::South => Direction::South,
+            Face::East => Direction::East,
+            Face::West => Direction::West,
+        }
+    }
+
+    /// Axis The Face Points Along (0 = x, 1 = y, 2 = z)
+    fn axis(self) -> usize {
+        self.offset().iter().position(|v| *v != 0).unwrap()
+    }
+
+    /// Corners Of This Face Of The Box `min..max`
+    fn quad(self, min: [f32; 3], max: [f32; 3]) -> [[f32; 3]; 4] {
+        let [x0, y0, z0] = min;
+        let [x1, y1, z1] = max;
+        match self {
+            Face::Up => [[x0, y1, z0], [x1, y1, z0], [x1, y1, z1], [x0, y1, z1]],
+            Face::Down => [[x0, y0, z1], [x1, y0, z1], [x1, y0, z0], [x0, y0, z0]],
+            Face::North => [[x0, y0, z1], [x0, y1, z1], [x1, y1, z1], [x1, y0, z1]],
+            Face::South => [[x1, y0, z0], [x1, y1, z0], [x0, y1, z0], [x0, y0, z0]],
+            Face::East => [[x1, y0, z1], [x1, y1, z1], [x1, y1, z0], [x1, y0, z0]],
+            Face::West => [[x0, y0, z0], [x0, y1, z0], [x0, y1, z1], [x0, y0, z1]],
+        }
+    }
+}
+
+/// Mesh Generation
+pub fn generate_chunk_mesh(
+    chunk: &Chunk,
+    borders: &NeighborBorders,
+    block_registry: &BlockRegistry,
+    strategy: MeshingStrategy,
+) -> Mesh {
+    let mut buffers = MeshBuffers::default();
 
     let lod_level = chunk.depth;
-    let step = 1 << lod_level;
-    
+    let step = (1 << lod_level) as f32;
+
+    // Uniform air chunks have nothing to draw
+    if chunk.uniform_block() != Some(BlockId::AIR) {
+        match strategy {
+            MeshingStrategy::Naive => naive_mesh(chunk, borders, block_registry, step, &mut buffers),
+            MeshingStrategy::Greedy => greedy_mesh(chunk, borders, block_registry, step, &mut buffers),
+        }
+    }
+
+    buffers.into_mesh()
+}
+
+/// Face Color, Darkening The Front Of Oriented Blocks
+fn face_color(block_registry: &BlockRegistry, state: BlockState, face: Face) -> [f32; 4] {
+    let [r, g, b, a] = block_registry.get_or_air(state.block()).debug_color.to_srgba().to_f32_array();
+    if block_registry.facing(state) == Some(face.direction()) {
+        [r * 0.5, g * 0.5, b * 0.5, a]
+    } else {
+        [r, g, b, a]
+    }
+}
+
+fn naive_mesh(
+    chunk: &Chunk,
+    borders: &NeighborBorders,
+    block_registry: &BlockRegistry,
+    scale: f32,
+    buffers: &mut MeshBuffers,
+) {
     for x in 0..CHUNK_SIZE {
         for y in 0..CHUNK_SIZE {
             for z in 0..CHUNK_SIZE {
-                let block_id = chunk.get_block(LocalPos::new(x as u8, y as u8, z as u8));
-                if block_registry.get_or_air(block_id).is_solid {
-                    add_block_faces(
-                        LocalPos::new(x as u8, y as u8, z as u8),
-                        block_id,
-                        chunk,
-                        block_registry,
-                        step as f32,
-                        &mut positions,
-                        &mut normals,
-                        &mut uvs,
-                        &mut colors,
-                        &mut indices,
-                    );
+                let pos = LocalPos::new(x, y, z);
+                let state = chunk.get_state(pos);
+                if !block_registry.get_or_air(state.block()).is_solid {
+                    continue;
+                }
+
+                let min = [x as f32 * scale, y as f32 * scale, z as f32 * scale];
+                let max = min.map(|v| v + scale);
+                for face in Face::ALL {
+                    if should_render_face(chunk, borders, pos, face) {
+                        let color = face_color(block_registry, state, face);
+                        buffers.push_quad(face.quad(min, max), face.normal(), color);
+                    }
                 }
             }
         }
     }
-
-
-    // Build Mesh
-    let mut mesh = Mesh::new(
-        bevy::mesh::PrimitiveTopology::TriangleList,
-        default(),
-    );
-    
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
-    mesh.insert_indices(bevy::mesh::Indices::U32(indices));
-
-    mesh
 }
 
-fn add_block_faces(
-    pos: LocalPos,
-    block_id: BlockId,
+/// Greedy Meshing
+///
+/// Every slice of the chunk along a face axis is turned into a 2D mask of
+/// visible faces, which is then covered with as few rectangles as possible.
+/// Only faces of the exact same state merge, so oriented blocks keep their
+/// front face.
+fn greedy_mesh(
     chunk: &Chunk,
+    borders: &NeighborBorders,
     block_registry: &BlockRegistry,
     scale: f32,
-    positions: &mut Vec<[f32; 3]>,
-    normals: &mut Vec<[f32; 3]>,
-    uvs: &mut Vec<[f32; 2]>,
-    colors: &mut Vec<[f32; 4]>,
-    indices: &mut Vec<u32>,
+    buffers: &mut MeshBuffers,
 ) {
-    let block_props = block_registry.get_or_air(block_id);
-    let color: [f32; 4] = block_props.debug_color.to_srgba().to_f32_array();
-
-    let x = pos.x as f32 * scale;
-    let y = pos.y as f32 * scale;
-    let z = pos.z as f32 * scale;
-
-    let faces = [
-        (
-            [0.0, 1.0, 0.0], // Up
-            should_render_face(chunk, pos, 0, 1, 0),
-            [
-                [x, y + scale, z],
-                [x + scale, y + scale, z],
-                [x + scale, y + scale, z + scale],
-                [x, y + scale, z + scale],
-            ],
-        ),
-        (
-            [0.0, -1.0, 0.0], // Down
-            should_render_face(chunk, pos, 0, -1, 0),
-            [
-                [x, y, z + scale],
-                [x + scale, y, z + scale],
-                [x + scale, y, z],
-                [x, y, z],
-            ],
-        ),
-        (
-            [0.0, 0.0, 1.0], // North (+Z)
-            should_render_face(chunk, pos, 0, 0, 1),
-            [
-                [x, y, z + scale],
-                [x, y + scale, z + scale],
-                [x + scale, y + scale, z + scale],
-                [x + scale, y, z + scale],
-            ],
-        ),
-        (
-            [0.0, 0.0, -1.0], // South (-Z)
-            should_render_face(chunk, pos, 0, 0, -1),
-            [
-                [x + scale, y, z],
-                [x + scale, y + scale, z],
-                [x, y + scale, z],
-                [x, y, z],
-            ],
-        ),
-        (
-            [1.0, 0.0, 0.0], // East (+X)
-            should_render_face(chunk, pos, 1, 0, 0),
-            [
-                [x + scale, y, z + scale],
-                [x + scale, y + scale, z + scale],
-                [x + scale, y + scale, z],
-                [x + scale, y, z],
-            ],
-        ),
-        (
-            [-1.0, 0.0, 0.0], // West (-X)
-            should_render_face(chunk, pos, -1, 0, 0),
-            [
-                [x, y, z],
-                [x, y + scale, z],
-                [x, y + scale, z + scale],
-                [x, y, z + scale],
-            ],
-        ),
-    ];
-
-    for (normal, should_render, verts) in faces {
-        if should_render {
-            let base_idx = positions.len() as u32;
-
-            for vert in verts {
-                positions.push(vert);
-                normals.push(normal);
-                uvs.push([0.0, 0.0]);
-                colors.push(color);
+    let size = CHUNK_SIZE as usize;
+    let mut mask: Vec<Option<BlockState>> = vec![None; size * size];
+
+    for face in Face::ALL {
+        let d = face.axis();
+        let (u, v) = ((d + 1) % 3, (d + 2) % 3);
+
+        for layer in 0..size {
+            // Visible faces of this slice, indexed [v][u]
+            for b in 0..size {
+                for a in 0..size {
+                    let mut coords = [0u8; 3];
+                    coords[d] = layer as u8;
+                    coords[u] = a as u8;
+                    coords[v] = b as u8;
+                    let pos = LocalPos::new(coords[0], coords[1], coords[2]);
+                    let state = chunk.get_state(pos);
+
+                    let visible = block_registry.get_or_air(state.block()).is_solid
+                        && should_render_face(chunk, borders, pos, face);
+                    mask[b * size + a] = visible.then_some(state);
+                }
             }
 
-            indices.extend_from_slice(&[
-                base_idx, base_idx + 2, base_idx + 1,
-                base_idx, base_idx + 3, base_idx + 2,
-            ]);
+            for b in 0..size {
+                let mut a = 0;
+                while a < size {
+                    let Some(state) = mask[b * size + a] else {
+                        a += 1;
+                        continue;
+                    };
+
+                    let mut width = 1;
+                    while a + width < size && mask[b * size + a + width] == Some(state) {
+                        width += 1;
+                    }
+
+                    let mut height = 1;
+                    while b + height < size
+                        && (a..a + width).all(|i| mask[(b + height) * size + i] == Some(state))
+                    {
+                        height += 1;
+                    }
+
+                    for row in b..b + height {
+                        mask[row * size + a..row * size + a + width].fill(None);
+                    }
+
+                    let mut min = [0.0; 3];
+                    let mut max = [0.0; 3];
+                    min[d] = layer as f32 * scale;
+                    max[d] = (layer + 1) as f32 * scale;
+                    min[u] = a as f32 * scale;
+                    max[u] = (a + width) as f32 * scale;
+                    min[v] = b as f32 * scale;
+                    max[v] = (b + height) as f32 * scale;
+
+                    let color = face_color(block_registry, state, face);
+                    buffers.push_quad(face.quad(min, max), face.normal(), color);
+
+                    a += width;
+                }
+            }
         }
     }
 }
 
-
 ///Chunk Render
-fn should_render_face(chunk: &Chunk, pos: LocalPos, dx: i32, dy: i32, dz: i32) -> bool {
+fn should_render_face(chunk: &Chunk, borders: &NeighborBorders, pos: LocalPos, face: Face) -> bool {
+    let [dx, dy, dz] = face.offset();
     let nx = pos.x as i32 + dx;
     let ny = pos.y as i32 + dy;
     let nz = pos.z as i32 + dz;
 
-    // Out-of-bounds faces are always visible
+    // Out-of-bounds faces are visible unless a loaded neighbor covers them
     if nx < 0 || nx >= CHUNK_SIZE as i32
         || ny < 0 || ny >= CHUNK_SIZE as i32
         || nz < 0 || nz >= CHUNK_SIZE as i32
     {
-        return true;
+        return borders
+            .outside(nx, ny, nz)
+            .is_none_or(|neighbor_block| neighbor_block == BlockId::AIR);
     }
 
     let neighbor_pos = LocalPos::new(nx as u8, ny as u8, nz as u8);
@@ -172,4 +423,159 @@ fn should_render_face(chunk: &Chunk, pos: LocalPos, dx: i32, dy: i32, dz: i32) -
 
     // Consider a block “empty” if neighbor is AIR or this LOD step skips multiple blocks
     neighbor_block == BlockId::AIR
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::chunk::CHUNK_VOLUME;
+    use bevy::mesh::VertexAttributeValues;
+    use std::collections::HashSet;
+
+    /// Unit Face Cells Covered By A Mesh (normal, min corner, color bits)
+    fn covered_cells(mesh: &Mesh) -> HashSet<([i32; 3], [i32; 3], [u32; 4])> {
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            panic!("missing positions");
+        };
+        let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute(Mesh::ATTRIBUTE_NORMAL) else {
+            panic!("missing normals");
+        };
+        let Some(VertexAttributeValues::Float32x4(colors)) = mesh.attribute(Mesh::ATTRIBUTE_COLOR) else {
+            panic!("missing colors");
+        };
+
+        let mut cells = HashSet::new();
+        for quad in 0..positions.len() / 4 {
+            let corners = &positions[quad * 4..quad * 4 + 4];
+            let normal = normals[quad * 4].map(|v| v as i32);
+            let color = colors[quad * 4].map(f32::to_bits);
+
+            let min: [i32; 3] = std::array::from_fn(|i| corners.iter().map(|c| c[i] as i32).min().unwrap());
+            let max: [i32; 3] = std::array::from_fn(|i| corners.iter().map(|c| c[i] as i32).max().unwrap());
+
+            for x in min[0]..max[0].max(min[0] + 1) {
+                for y in min[1]..max[1].max(min[1] + 1) {
+                    for z in min[2]..max[2].max(min[2] + 1) {
+                        assert!(cells.insert((normal, [x, y, z], color)), "overlapping quads");
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    fn quad_count(mesh: &Mesh) -> usize {
+        mesh.count_vertices() / 4
+    }
+
+    #[test]
+    fn test_greedy_covers_same_area_as_naive() {
+        let registry = BlockRegistry::new();
+        let mut chunk = Chunk::test_chunk(ChunkPos::new(0, 0, 0), 0);
+
+        // Break up the flat layers with some holes and pillars
+        for i in 0..CHUNK_SIZE {
+            chunk.set_block(LocalPos::new(i, 6, (i * 7) % CHUNK_SIZE), BlockId::AIR);
+            chunk.set_block(LocalPos::new((i * 3) % CHUNK_SIZE, 7 + i % 5, i), BlockId::STONE);
+        }
+
+        let borders = NeighborBorders::default();
+        let naive = generate_chunk_mesh(&chunk, &borders, &registry, MeshingStrategy::Naive);
+        let greedy = generate_chunk_mesh(&chunk, &borders, &registry, MeshingStrategy::Greedy);
+
+        assert_eq!(covered_cells(&naive), covered_cells(&greedy));
+        assert!(quad_count(&greedy) < quad_count(&naive));
+    }
+
+    #[test]
+    fn test_greedy_merges_uniform_chunk() {
+        let registry = BlockRegistry::new();
+        let mut chunk = Chunk::empty(ChunkPos::new(0, 0, 0), 1);
+        for index in 0..CHUNK_VOLUME {
+            chunk.set_block(LocalPos::from_index(index), BlockId::STONE);
+        }
+
+        let borders = NeighborBorders::default();
+        let naive = generate_chunk_mesh(&chunk, &borders, &registry, MeshingStrategy::Naive);
+        let greedy = generate_chunk_mesh(&chunk, &borders, &registry, MeshingStrategy::Greedy);
+
+        // One quad per side, scaled by the LOD step
+        assert_eq!(quad_count(&greedy), 6);
+        assert_eq!(covered_cells(&naive), covered_cells(&greedy));
+    }
+
+    #[test]
+    fn test_oriented_block_front_face() {
+        let registry = BlockRegistry::new();
+        let mut chunk = Chunk::empty(ChunkPos::new(0, 0, 0), 0);
+        let furnace = registry.with_facing(BlockId::FURNACE.into(), Direction::East);
+        chunk.set_state(LocalPos::new(4, 4, 4), furnace);
+        chunk.set_state(LocalPos::new(5, 4, 4), BlockId::FURNACE.into());
+
+        let borders = NeighborBorders::default();
+        let naive = generate_chunk_mesh(&chunk, &borders, &registry, MeshingStrategy::Naive);
+        let greedy = generate_chunk_mesh(&chunk, &borders, &registry, MeshingStrategy::Greedy);
+        assert_eq!(covered_cells(&naive), covered_cells(&greedy));
+
+        // The east front is hidden by the second furnace, whose default front faces north
+        let base = face_color(&registry, BlockId::FURNACE.into(), Face::Up).map(f32::to_bits);
+        let darkened: Vec<_> = covered_cells(&greedy)
+            .into_iter()
+            .filter(|(_, _, color)| *color != base)
+            .map(|(normal, _, _)| normal)
+            .collect();
+        assert_eq!(darkened, vec![[0, 0, 1]]);
+    }
+
+    #[test]
+    fn test_loaded_neighbor_culls_border_faces() {
+        let registry = BlockRegistry::new();
+        let chunk = Chunk::test_chunk(ChunkPos::new(0, 0, 0), 0);
+        let east = Chunk::test_chunk(ChunkPos::new(1, 0, 0), 0);
+
+        let alone = generate_chunk_mesh(&chunk, &NeighborBorders::default(), &registry, MeshingStrategy::Naive);
+        let borders = NeighborBorders::capture(&chunk, |pos| (pos == east.pos).then_some(&east));
+        let joined = generate_chunk_mesh(&chunk, &borders, &registry, MeshingStrategy::Naive);
+
+        let east_faces = |mesh: &Mesh| {
+            covered_cells(mesh)
+                .into_iter()
+                .filter(|(normal, _, _)| *normal == [1, 0, 0])
+                .count()
+        };
+        // Seven filled layers of 32 blocks each face east
+        assert_eq!(east_faces(&alone), 7 * CHUNK_SIZE as usize);
+        assert_eq!(east_faces(&joined), 0);
+        assert_eq!(covered_cells(&alone).len() - covered_cells(&joined).len(), 7 * CHUNK_SIZE as usize);
+    }
+
+    #[test]
+    fn test_finer_neighbor_culls_only_fully_covered_faces() {
+        let registry = BlockRegistry::new();
+        // Solid up to y = 14 in two-block steps
+        let coarse = Chunk::test_chunk(ChunkPos::new(1, 0, 0), 1);
+        // Solid up to y = 7, touching a quarter of the coarse west face
+        let fine = Chunk::test_chunk(ChunkPos::new(0, 0, 0), 0);
+
+        let west_faces = |mesh: &Mesh| {
+            covered_cells(mesh)
+                .into_iter()
+                .filter(|(normal, _, _)| *normal == [-1, 0, 0])
+                .count()
+        };
+
+        let alone = generate_chunk_mesh(&coarse, &NeighborBorders::default(), &registry, MeshingStrategy::Naive);
+        let borders = NeighborBorders::capture(&coarse, |pos| (pos == fine.pos).then_some(&fine));
+        let joined = generate_chunk_mesh(&coarse, &borders, &registry, MeshingStrategy::Naive);
+
+        // Coarse rows 0..3 sit in front of fully solid fine blocks, row 3 is half air
+        assert_eq!(west_faces(&alone) - west_faces(&joined), 3 * 16 * 4);
+
+        // The coarse chunk covers the whole east face of the fine chunk
+        let borders = NeighborBorders::capture(&fine, |pos| {
+            (pos.x == 1 && (0..2).contains(&pos.y) && (0..2).contains(&pos.z)).then_some(&coarse)
+        });
+        let joined = generate_chunk_mesh(&fine, &borders, &registry, MeshingStrategy::Naive);
+        assert!(covered_cells(&joined).iter().all(|(normal, _, _)| *normal != [1, 0, 0]));
+    }
+}
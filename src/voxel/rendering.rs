@@ -1,41 +1,147 @@
 use crate::core::block::BlockRegistry;
-use crate::voxel::meshing::generate_chunk_mesh;
+use crate::core::position::ChunkPos;
+use crate::voxel::meshing::{generate_chunk_mesh, MeshingStrategy, NeighborBorders};
 use crate::world::chunk::{Chunk, NeedsMesh};
+use crate::world::chunk_manager::{touching_positions, ChunkManager};
+use crate::world::octree::Octree;
 use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-/// Generate Needed Meshes
+/// In-Flight Mesh Tasks
+#[derive(Resource)]
+pub struct ChunkMeshTasks {
+    tasks: HashMap<Entity, Task<Mesh>>,
+    // Shared with tasks, refreshed when the registry changes
+    registry: Option<Arc<BlockRegistry>>,
+    pub uploads_per_frame: usize,
+}
+
+impl Default for ChunkMeshTasks {
+    fn default() -> Self {
+        Self {
+            tasks: HashMap::new(),
+            registry: None,
+            uploads_per_frame: 16,
+        }
+    }
+}
+
+impl ChunkMeshTasks {
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+/// Start Meshing Tasks For Marked Chunks
 pub fn mesh_chunks(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut mesh_tasks: ResMut<ChunkMeshTasks>,
     block_registry: Res<BlockRegistry>,
-    mut query: Query<(Entity, &mut Chunk), With<NeedsMesh>>,
+    strategy: Res<MeshingStrategy>,
+    chunk_manager: Res<ChunkManager>,
+    octree: Res<Octree>,
+    mut query: Query<(Entity, &mut Chunk, Has<NeedsMesh>)>,
 ) {
-    for (entity, mut chunk) in query.iter_mut() {
+    if block_registry.is_changed() || mesh_tasks.registry.is_none() {
+        mesh_tasks.registry = Some(Arc::new(block_registry.clone()));
+    }
+    let Some(registry) = mesh_tasks.registry.clone() else {
+        return;
+    };
 
+    let mut jobs = Vec::new();
+    for (entity, chunk, needs_mesh) in query.iter() {
+        if !needs_mesh {
+            continue;
+        }
+        commands.entity(entity).remove::<NeedsMesh>();
         if !chunk.dirty {
-            commands.entity(entity).remove::<NeedsMesh>();
             continue;
         }
 
-        let mesh = generate_chunk_mesh(&chunk, &block_registry);
-        let mesh_handle = meshes.add(mesh);
-        
-        let material = materials.add(StandardMaterial {
-            base_color: Color::WHITE,
-            perceptual_roughness: 0.8,
-            reflectance: 0.2,
-            ..default()
+        // Neighbors at any LOD, found through the octree
+        let borders = NeighborBorders::capture(chunk, |pos| {
+            let neighbor = chunk_manager.chunk_covering(&octree, pos, |entity| {
+                query.get(entity).ok().map(|(_, chunk, _)| chunk.depth)
+            })?;
+            query.get(neighbor).ok().map(|(_, chunk, _)| chunk)
         });
+        jobs.push((entity, chunk.clone(), borders));
+    }
 
-        // Mesh and Material
-        commands.entity(entity).insert(Mesh3d(mesh_handle));
-        commands.entity(entity).insert(MeshMaterial3d(material));
-        commands.entity(entity).remove::<NeedsMesh>();
+    let pool = AsyncComputeTaskPool::get();
+    for (entity, snapshot, borders) in jobs {
+        // Clearing the flag is bookkeeping, not an edit
+        if let Ok((_, mut chunk, _)) = query.get_mut(entity) {
+            chunk.bypass_change_detection().dirty = false;
+        }
+
+        let registry = registry.clone();
+        let strategy = *strategy;
+        let task = pool.spawn(async move { generate_chunk_mesh(&snapshot, &borders, &registry, strategy) });
 
+        // Replacing an older task drops (cancels) it
+        mesh_tasks.tasks.insert(entity, task);
+    }
+}
+
+/// Swap In Finished Meshes (bounded per frame)
+pub fn upload_chunk_meshes(
+    mut commands: Commands,
+    mut mesh_tasks: ResMut<ChunkMeshTasks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut material: Local<Option<Handle<StandardMaterial>>>,
+    chunks: Query<&Chunk>,
+) {
+    let material = material
+        .get_or_insert_with(|| {
+            materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                perceptual_roughness: 0.8,
+                reflectance: 0.2,
+                ..default()
+            })
+        })
+        .clone();
+
+    let budget = mesh_tasks.uploads_per_frame;
+    let mut finished = Vec::new();
+    for (entity, task) in mesh_tasks.tasks.iter_mut() {
+        if finished.len() >= budget {
+            break;
+        }
+        if let Some(mesh) = check_ready(task) {
+            finished.push((*entity, mesh));
+        }
+    }
+
+    for (entity, mesh) in finished {
+        mesh_tasks.tasks.remove(&entity);
+
+        // Chunk unloaded while its mesh was being built
+        let Ok(chunk) = chunks.get(entity) else {
+            continue;
+        };
+
+        // Mesh and Material
+        commands.entity(entity).insert((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(material.clone()),
+        ));
 
         info!("Generated mesh for chunk at {:?}", chunk.pos);
     }
+
+    // Drop tasks whose chunk is gone
+    mesh_tasks.tasks.retain(|entity, _| chunks.contains(*entity));
 }
 
 /// Mark Dirty Chunks
@@ -50,6 +156,66 @@ pub fn mark_dirty_chunks(
     }
 }
 
+/// Remesh Neighbors Whose Shared Border Changed
+///
+/// A chunk culls its border faces against the neighbors that were loaded
+/// when it was meshed, so loading, unloading or editing a border layer
+/// invalidates the chunks on the other side, whatever their LOD.
+pub fn remesh_border_neighbors(
+    mut commands: Commands,
+    chunk_manager: Res<ChunkManager>,
+    octree: Res<Octree>,
+    mut removed: RemovedComponents<Chunk>,
+    mut known: Local<HashMap<Entity, (ChunkPos, u8)>>,
+    mut query: Query<(Entity, &mut Chunk)>,
+) {
+    // (chunk position, depth, changed faces)
+    let mut sources: Vec<(ChunkPos, u8, u8)> = Vec::new();
+
+    for entity in removed.read() {
+        if let Some((pos, depth)) = known.remove(&entity) {
+            sources.push((pos, depth, 0b11_1111));
+        }
+    }
+
+    for (entity, mut chunk) in query.iter_mut() {
+        if chunk.is_added() {
+            known.insert(entity, (chunk.pos, chunk.depth));
+            chunk.bypass_change_detection().take_edited_faces();
+            sources.push((chunk.pos, chunk.depth, 0b11_1111));
+        } else if chunk.is_changed() {
+            let faces = chunk.bypass_change_detection().take_edited_faces();
+            if faces != 0 {
+                sources.push((chunk.pos, chunk.depth, faces));
+            }
+        }
+    }
+
+    let mut targets = HashSet::new();
+    for (pos, depth, faces) in sources {
+        for face in (0..6).filter(|face| faces & (1 << face) != 0) {
+            for touching in touching_positions(pos, depth, face) {
+                let covering = chunk_manager.chunk_covering(&octree, touching, |entity| {
+                    query.get(entity).ok().map(|(_, chunk)| chunk.depth)
+                });
+                if let Some(entity) = covering
+                    && query.get(entity).is_ok_and(|(_, chunk)| chunk.pos != pos)
+                {
+                    targets.insert(entity);
+                }
+            }
+        }
+    }
+
+    for entity in targets {
+        if let Ok((_, mut neighbor)) = query.get_mut(entity) {
+            // Not an edit, so it must not spread any further
+            neighbor.bypass_change_detection().dirty = true;
+            commands.entity(entity).insert(NeedsMesh);
+        }
+    }
+}
+
 /// Mark Initial Chunks for Meshing
 pub fn mark_initial_chunks(
     mut commands: Commands,
@@ -58,4 +224,4 @@ pub fn mark_initial_chunks(
     for entity in query.iter() {
         commands.entity(entity).insert(NeedsMesh);
     }
-}
\ No newline at end of file
+}
@@ -1,18 +1,27 @@
-use crate::core::{block::BlockId, position::{ChunkPos, LocalPos, CHUNK_SIZE}};
+use crate::core::{block::{BlockId, BlockState}, position::{BlockPos, ChunkPos, LocalPos, CHUNK_SIZE}};
+use crate::machine::processing::Machine;
+use crate::multiblock::registry::{Orientation, SlotRole};
+use crate::world::generation::GenerationStage;
+use crate::world::palette::PalettedStorage;
 use bevy::prelude::*;
 use std::collections::HashMap;
 
-const CHUNK_VOLUME: usize = (CHUNK_SIZE as usize).pow(3);
+pub const CHUNK_VOLUME: usize = (CHUNK_SIZE as usize).pow(3);
 
 /// 25x25x25 (May change later)
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct Chunk {
     pub pos: ChunkPos,
-    blocks: Box<[BlockId; CHUNK_VOLUME]>,
+    blocks: PalettedStorage,
     // Extra Block Data
     pub block_entities: HashMap<LocalPos, BlockEntity>,
     pub dirty: bool,
     pub depth: u8,
+    pub stage: GenerationStage,
+    // Edited since last load/save
+    needs_save: bool,
+    // Faces touched by edits since neighbors were last told, in `ChunkPos::neighbors` order
+    edited_faces: u8,
 }
 
 impl Chunk {
@@ -20,54 +29,25 @@ impl Chunk {
     pub fn empty(pos: ChunkPos, depth: u8) -> Self {
         Self {
             pos,
-            blocks: Box::new([BlockId::AIR; CHUNK_VOLUME]),
+            blocks: PalettedStorage::new(CHUNK_VOLUME, BlockState::AIR),
             block_entities: HashMap::new(),
             dirty: true,
             depth,
+            stage: GenerationStage::Empty,
+            needs_save: false,
+            edited_faces: 0,
         }
     }
 
-pub fn generate_chunk(pos: ChunkPos, depth: u8) -> Self {
-    let mut chunk = Self::empty(pos, depth);
-
-    use noise::{NoiseFn, Perlin};
-    let perlin = Perlin::new(0);
-
-    let frequency = 0.01;
-    let amplitude = 100.0;
-    let base_height = -80;
-
-    let scale = 1 << depth;
-
-    // Use chunk's world position as the base — do NOT re-snap it
-    let chunk_world_pos = pos.to_world_pos();
-
-    for z in 0..CHUNK_SIZE {
-        for x in 0..CHUNK_SIZE {
-            // World coordinates, scale each block correctly
-            let world_x = chunk_world_pos.x + (x as i32) * scale;
-            let world_z = chunk_world_pos.z + (z as i32) * scale;
-
-            let noise_value = perlin.get([world_x as f64 * frequency, world_z as f64 * frequency]);
-            let height = ((noise_value + 1.0) * 0.5 * amplitude) as i32 + base_height;
-
-            for y in 0..CHUNK_SIZE {
-                let world_y = chunk_world_pos.y + (y as i32) * scale;
-
-                if world_y <= height - 3 {
-                    chunk.set_block(LocalPos::new(x, y, z), BlockId::STONE);
-                } else if world_y <= height - 1 {
-                    chunk.set_block(LocalPos::new(x, y, z), BlockId::DIRT);
-                } else if world_y <= height + scale {
-                    chunk.set_block(LocalPos::new(x, y, z), BlockId::GRASS);
-                }
-            }
+    /// Chunk From Loaded Block Data
+    pub fn from_blocks(pos: ChunkPos, depth: u8, blocks: PalettedStorage) -> Self {
+        Self {
+            blocks,
+            stage: GenerationStage::Complete,
+            ..Self::empty(pos, depth)
         }
     }
 
-    chunk
-}
-
     /// Test Chunk
     pub fn test_chunk(pos: ChunkPos, depth: u8) -> Self {
         let mut chunk = Self::empty(pos, depth);
@@ -95,37 +75,109 @@ pub fn generate_chunk(pos: ChunkPos, depth: u8) -> Self {
             }
         }
 
+        chunk.stage = GenerationStage::Complete;
+        chunk.needs_save = false;
         chunk
     }
 
     pub fn get_block(&self, pos: LocalPos) -> BlockId {
-        self.blocks[pos.to_index()]
+        self.get_state(pos).block()
     }
 
+    /// Set A Block In Its Default State
     pub fn set_block(&mut self, pos: LocalPos, block: BlockId) {
-        self.blocks[pos.to_index()] = block;
+        self.set_state(pos, block.into());
+    }
+
+    pub fn get_state(&self, pos: LocalPos) -> BlockState {
+        self.blocks.get(pos.to_index())
+    }
+
+    pub fn set_state(&mut self, pos: LocalPos, state: BlockState) {
+        self.blocks.set(pos.to_index(), state);
         self.dirty = true;
+        self.needs_save = true;
+        self.edited_faces |= Self::border_faces(pos);
+    }
+
+    /// Faces A Local Position Lies On (bit per `ChunkPos::neighbors` entry)
+    pub fn border_faces(pos: LocalPos) -> u8 {
+        let edge = CHUNK_SIZE - 1;
+        let mut faces = 0;
+        for (axis, value) in [pos.x, pos.y, pos.z].into_iter().enumerate() {
+            if value == edge {
+                faces |= 1 << (axis * 2);
+            }
+            if value == 0 {
+                faces |= 1 << (axis * 2 + 1);
+            }
+        }
+        faces
+    }
+
+    /// Border Faces Edited Since The Last Call
+    pub fn take_edited_faces(&mut self) -> u8 {
+        std::mem::take(&mut self.edited_faces)
+    }
+
+    pub fn blocks(&self) -> &PalettedStorage {
+        &self.blocks
+    }
+
+    /// Only full resolution edits are worth persisting
+    pub fn needs_save(&self) -> bool {
+        self.needs_save && self.depth == 0
+    }
+
+    /// Persist Block Entity Changes (without remeshing when change detection is bypassed)
+    pub fn mark_needs_save(&mut self) {
+        self.needs_save = true;
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.needs_save = false;
+    }
+
+    /// Block Filling The Whole Chunk (uniform fast path)
+    pub fn uniform_block(&self) -> Option<BlockId> {
+        self.uniform_state().map(BlockState::block)
+    }
+
+    pub fn uniform_state(&self) -> Option<BlockState> {
+        self.blocks.single_value()
+    }
+
+    /// Shrink Palette After Bulk Edits
+    pub fn compact(&mut self) {
+        self.blocks.compact();
     }
 
     /// Iterate
     pub fn iter_blocks(&self) -> impl Iterator<Item = (LocalPos, BlockId)> + '_ {
+        let all_air = self.uniform_block() == Some(BlockId::AIR);
         self.blocks
             .iter()
             .enumerate()
-            .filter(|(_, id)| **id != BlockId::AIR)
-            .map(|(idx, id)| (LocalPos::from_index(idx), *id))
+            .take(if all_air { 0 } else { CHUNK_VOLUME })
+            .map(|(idx, state)| (LocalPos::from_index(idx), state.block()))
+            .filter(|(_, id)| *id != BlockId::AIR)
     }
     
 }
 
 /// Extra Block Data
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BlockEntity {
-    Machine {
-        // Will implement later
-    },
+    Machine(Box<Machine>),
+    /// Cell of a formed multiblock
     MultiblockPart {
-        // Will implement later
+        controller: BlockPos,
+        role: Option<SlotRole>,
+    },
+    /// Controller of a formed multiblock
+    MultiblockController {
+        pattern: String,
+        orientation: Orientation,
     },
     Storage {
         // Will implement later
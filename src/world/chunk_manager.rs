@@ -1,6 +1,10 @@
 use crate::core::position::{ChunkPos, CHUNK_SIZE};
 use crate::world::chunk::Chunk;
+use crate::world::generation::ActiveGenerator;
+use crate::world::lod::{build_from_loaded, LodCache};
+use crate::world::pipeline::{ChunkKey, GenerationPipeline};
 use crate::world::octree::Octree;
+use crate::world::region::{save_or_warn, RegionStorage};
 use bevy::prelude::*;
 use std::collections::{HashMap, HashSet};
 
@@ -86,6 +90,52 @@ impl ChunkManager {
     pub fn get_chunk_entity(&self, pos: ChunkPos) -> Option<Entity> {
         self.loaded_chunks.get(&pos).copied()
     }
+
+    /// Loaded Chunk Covering A Position At Any LOD
+    ///
+    /// Walks the octree nodes containing the position, finest first, and
+    /// returns the first one whose chunk is loaded at that node's LOD.
+    pub fn chunk_covering(
+        &self,
+        octree: &Octree,
+        pos: ChunkPos,
+        depth_of: impl Fn(Entity) -> Option<u8>,
+    ) -> Option<Entity> {
+        let size = CHUNK_SIZE as f32;
+        let center = chunk_center(pos);
+
+        octree.path_to(center).into_iter().rev().find_map(|node| {
+            let node_min = node.center - Vec3::splat(node.size / 2.0);
+            let lod = (node.size / size).log2() as u8;
+            self.get_chunk_entity(ChunkPos::from_world_pos(node_min))
+                .filter(|entity| depth_of(*entity) == Some(lod))
+        })
+    }
+}
+
+/// Center Of A Full Resolution Chunk In World Space
+fn chunk_center(pos: ChunkPos) -> Vec3 {
+    let size = CHUNK_SIZE as f32;
+    Vec3::new(pos.x as f32, pos.y as f32, pos.z as f32) * size + Vec3::splat(size / 2.0)
+}
+
+/// Chunk Positions Just Outside One Face Of A Chunk (`ChunkPos::neighbors` order)
+pub fn touching_positions(pos: ChunkPos, depth: u8, face: usize) -> Vec<ChunkPos> {
+    let step = 1 << depth;
+    let axis = face / 2;
+    let outside = if face.is_multiple_of(2) { step } else { -1 };
+
+    let mut positions = Vec::with_capacity((step * step) as usize);
+    for b in 0..step {
+        for a in 0..step {
+            let mut offset = [0; 3];
+            offset[axis] = outside;
+            offset[(axis + 1) % 3] = a;
+            offset[(axis + 2) % 3] = b;
+            positions.push(ChunkPos::new(pos.x + offset[0], pos.y + offset[1], pos.z + offset[2]));
+        }
+    }
+    positions
 }
 
 pub fn update_chunks_around_player(
@@ -93,6 +143,9 @@ pub fn update_chunks_around_player(
     mut chunk_manager: ResMut<ChunkManager>,
     camera_query: Query<&Transform, With<Camera>>,
     mut octree: ResMut<Octree>,
+    mut pipeline: ResMut<GenerationPipeline>,
+    region_storage: Res<RegionStorage>,
+    chunks: Query<&Chunk>,
 ) {
     if let Ok(camera_transform) = camera_query.single() {
 
@@ -112,36 +165,66 @@ pub fn update_chunks_around_player(
             })
             .collect();
 
+        let mut wanted = HashSet::new();
         for node in &leaves {
             let node_min = node.center - Vec3::splat(node.size / 2.0);
             let chunk_pos = ChunkPos::from_world_pos(node_min);
 
-            if !chunk_manager.is_loaded(chunk_pos) {
-                // Compute LOD based on node size relative to base CHUNK_SIZE
-                let lod = (node.size / CHUNK_SIZE as f32).log2() as u8;
-
-                let chunk = Chunk::generate_chunk(chunk_pos, lod);
+            // Compute LOD based on node size relative to base CHUNK_SIZE
+            let lod = (node.size / CHUNK_SIZE as f32).log2() as u8;
 
-                let entity = commands.spawn((
-                    chunk,
-                    Transform::from_translation(node_min),
-                    GlobalTransform::default(),
-                )).id();
+            // A split or merged node keeps its corner but changes LOD
+            let up_to_date = chunk_manager
+                .get_chunk_entity(chunk_pos)
+                .and_then(|entity| chunks.get(entity).ok())
+                .is_some_and(|chunk| chunk.depth == lod);
 
-                chunk_manager.register_chunk(chunk_pos, entity);
-                info!("Spawned chunk at {:?} (size {:.1}, lod {})", node_min, node.size, lod);
+            if !up_to_date {
+                let key = ChunkKey::new(chunk_pos, lod);
+                pipeline.request(key);
+                wanted.insert(key);
             }
         }
 
-        // Unload chunks that are no longer in leaves
+        // Leaves that merged or split before their chunk finished
+        let stale: Vec<ChunkKey> = pipeline
+            .requested()
+            .filter(|key| !wanted.contains(key))
+            .copied()
+            .collect();
+        for key in stale {
+            pipeline.cancel(key);
+        }
+
+        // Unload chunks that are no longer in leaves, but only once the leaf
+        // covering them is loaded, so merges can still read the finer data
         let mut to_unload = Vec::new();
         for (&pos, &entity) in chunk_manager.loaded_chunks.iter() {
-            if !leaf_positions.contains(&pos) {
+            if leaf_positions.contains(&pos) {
+                continue;
+            }
+            let replaced = octree.path_to(chunk_center(pos)).last().is_none_or(|leaf| {
+                let lod = (leaf.size / CHUNK_SIZE as f32).log2() as u8;
+                let leaf_pos = ChunkPos::from_world_pos(leaf.center - Vec3::splat(leaf.size / 2.0));
+                chunk_manager
+                    .get_chunk_entity(leaf_pos)
+                    .and_then(|entity| chunks.get(entity).ok())
+                    .is_some_and(|chunk| chunk.depth == lod)
+            });
+            if replaced {
                 to_unload.push((pos, entity));
             }
         }
 
         for (pos, entity) in to_unload {
+            // Persist edits before the chunk is gone, keeping it loaded to retry if that fails
+            if let Ok(chunk) = chunks.get(entity)
+                && chunk.needs_save()
+                && save_or_warn(&region_storage, chunk).is_err()
+            {
+                continue;
+            }
+
             commands.entity(entity).despawn();
             chunk_manager.unregister_chunk(pos);
             info!("Unloaded chunk at {:?}", pos);
@@ -149,6 +232,99 @@ pub fn update_chunks_around_player(
     }
 }
 
+/// Spawn Chunks That Finished The Generation Pipeline
+pub fn spawn_generated_chunks(
+    mut commands: Commands,
+    mut chunk_manager: ResMut<ChunkManager>,
+    mut pipeline: ResMut<GenerationPipeline>,
+    region_storage: Res<RegionStorage>,
+    lod_cache: Res<LodCache>,
+    generator: Res<ActiveGenerator>,
+    chunks: Query<&Chunk>,
+) {
+    for chunk in pipeline.advance(&generator.0, &region_storage, &lod_cache) {
+        spawn_chunk(&mut commands, &mut chunk_manager, &region_storage, &chunks, chunk);
+    }
+}
+
+/// Build Merged Octree Leaves From The Finer Chunks They Replace
+///
+/// Runs after the octree update. The finer chunks stay loaded until their
+/// covering leaf is, so the coarse chunk shows exactly what was loaded
+/// (including edits) and is cached for later. The noise generated request
+/// for the same leaf is dropped.
+pub fn merge_lod_chunks(
+    mut commands: Commands,
+    mut chunk_manager: ResMut<ChunkManager>,
+    mut lod_cache: ResMut<LodCache>,
+    mut pipeline: ResMut<GenerationPipeline>,
+    octree: Res<Octree>,
+    region_storage: Res<RegionStorage>,
+    chunks: Query<&Chunk>,
+) {
+    let lookup = |key: ChunkKey| {
+        chunk_manager
+            .get_chunk_entity(key.pos)
+            .and_then(|entity| chunks.get(entity).ok())
+            .filter(|chunk| chunk.depth == key.depth)
+    };
+
+    let mut merged = Vec::new();
+    for node in octree.collect_leaves() {
+        let node_min = node.center - Vec3::splat(node.size / 2.0);
+        let lod = (node.size / CHUNK_SIZE as f32).log2() as u8;
+        let key = ChunkKey::new(ChunkPos::from_world_pos(node_min), lod);
+
+        if lod == 0 || lookup(key).is_some() {
+            continue;
+        }
+        if let Some(chunk) = build_from_loaded(key, &lookup) {
+            merged.push(chunk);
+        }
+    }
+
+    for chunk in merged {
+        pipeline.cancel(ChunkKey::new(chunk.pos, chunk.depth));
+        lod_cache.store(&chunk);
+        spawn_chunk(&mut commands, &mut chunk_manager, &region_storage, &chunks, chunk);
+    }
+}
+
+/// Spawn A Finished Chunk, Replacing Any Other LOD At Its Position
+fn spawn_chunk(
+    commands: &mut Commands,
+    chunk_manager: &mut ChunkManager,
+    region_storage: &RegionStorage,
+    chunks: &Query<&Chunk>,
+    chunk: Chunk,
+) {
+    let chunk_pos = chunk.pos;
+
+    // Replace the old LOD only now so there is never a hole
+    if let Some(old) = chunk_manager.get_chunk_entity(chunk_pos) {
+        // Unsaved edits win, the new chunk is requested again later
+        if let Ok(old_chunk) = chunks.get(old)
+            && old_chunk.needs_save()
+            && save_or_warn(region_storage, old_chunk).is_err()
+        {
+            return;
+        }
+        commands.entity(old).despawn();
+        chunk_manager.unregister_chunk(chunk_pos);
+    }
+
+    let lod = chunk.depth;
+    let node_min = chunk_pos.to_world_pos().to_vec3();
+    let entity = commands.spawn((
+        chunk,
+        Transform::from_translation(node_min),
+        GlobalTransform::default(),
+    )).id();
+
+    chunk_manager.register_chunk(chunk_pos, entity);
+    info!("Spawned chunk at {:?} (lod {})", node_min, lod);
+}
+
 /// Test Chunks
 pub fn spawn_initial_chunks(
     mut commands: Commands,
@@ -167,4 +343,69 @@ pub fn spawn_initial_chunks(
     chunk_manager.register_chunk(chunk_pos, entity);
     
     info!("Spawned test chunk at {:?}", chunk_pos);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockId;
+    use crate::core::position::LocalPos;
+
+    #[test]
+    fn test_merge_builds_from_loaded_children() {
+        let root = std::env::temp_dir().join(format!("aeternitas_merge_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let mut world = World::new();
+        let size = CHUNK_SIZE as f32;
+        // One coarse node over eight full resolution chunks
+        world.insert_resource(Octree::new(Vec3::splat(size), size * 2.0, size, 1));
+        world.init_resource::<ChunkManager>();
+        world.init_resource::<GenerationPipeline>();
+        world.insert_resource(RegionStorage::new(root.join("regions")));
+        world.insert_resource(LodCache::new(root.join("lod")));
+        let camera = world.spawn((Camera::default(), Transform::from_translation(Vec3::splat(size)))).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((update_chunks_around_player, merge_lod_chunks.after(update_chunks_around_player)));
+
+        // Split, then stand in for the pipeline with one edited child
+        schedule.run(&mut world);
+        let keys: Vec<ChunkKey> = world.resource::<GenerationPipeline>().requested().copied().collect();
+        assert_eq!(keys.len(), 8);
+        for key in keys {
+            let mut chunk = Chunk::empty(key.pos, 0);
+            if key.pos == ChunkPos::new(1, 0, 0) {
+                for z in 0..CHUNK_SIZE {
+                    for y in 0..CHUNK_SIZE {
+                        for x in 0..CHUNK_SIZE {
+                            chunk.set_block(LocalPos::new(x, y, z), BlockId::STONE);
+                        }
+                    }
+                }
+            }
+            let entity = world.spawn(chunk).id();
+            world.resource_mut::<ChunkManager>().register_chunk(key.pos, entity);
+            world.resource_mut::<GenerationPipeline>().cancel(key);
+        }
+
+        // Walking away merges the octree, the children survive until the coarse chunk exists
+        world.get_mut::<Transform>(camera).unwrap().translation = Vec3::new(size, size, size * 10.0);
+        schedule.run(&mut world);
+        assert!(world.resource::<GenerationPipeline>().requested().next().is_none());
+        let manager = world.resource::<ChunkManager>();
+        assert_eq!(manager.loaded_chunks.len(), 8);
+        let coarse = world.get::<Chunk>(manager.get_chunk_entity(ChunkPos::new(0, 0, 0)).unwrap()).unwrap();
+        assert_eq!(coarse.depth, 1);
+        assert_eq!(coarse.get_block(LocalPos::new(20, 5, 5)), BlockId::STONE);
+        assert_eq!(coarse.get_block(LocalPos::new(5, 5, 5)), BlockId::AIR);
+
+        // Now the children can go, their edits saved on the way out
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<ChunkManager>().loaded_chunks.len(), 1);
+        let saved = world.resource::<RegionStorage>().load_chunk(ChunkPos::new(1, 0, 0)).unwrap().unwrap();
+        assert_eq!(saved.get_block(LocalPos::new(7, 7, 7)), BlockId::STONE);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::core::block::{BlockState, Direction};
+use crate::core::inventory::ItemStack;
+use crate::core::position::BlockPos;
+use crate::world::voxel_world::VoxelWorld;
+use bevy::prelude::*;
+
+/// What Caused A Block To Change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockChangeCause {
+    Player,
+    Machine,
+    Multiblock,
+    Fluid,
+    /// Falling blocks, growth, random ticks and other world rules
+    World,
+    /// A reaction to a neighbor update
+    Neighbor,
+}
+
+/// Sent For Every Block Written Through `VoxelWorld`
+///
+/// State-only changes, like a machine turning on, count as changes too.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockChanged {
+    pub pos: BlockPos,
+    pub old: BlockState,
+    pub new: BlockState,
+    pub cause: BlockChangeCause,
+}
+
+/// Sent To Each Loaded Block Next To A Changed One
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeighborUpdate {
+    /// Block being notified
+    pub pos: BlockPos,
+    pub block: BlockState,
+    /// Side of `pos` the change happened on
+    pub direction: Direction,
+    pub change: BlockChanged,
+}
+
+/// Sent When Items Spill Out Of A Block, Like A Broken Machine's Contents
+#[derive(Message, Debug, Clone, PartialEq)]
+pub struct ItemsDropped {
+    pub pos: BlockPos,
+    pub stacks: Vec<ItemStack>,
+}
+
+/// Neighbor Update Pass
+///
+/// Machines, multiblocks, fluids and falling blocks react by reading
+/// `NeighborUpdate`. Changes they make send new `BlockChanged` messages,
+/// which are handled on a later pass so chains of updates cannot loop
+/// within one frame.
+pub fn notify_block_neighbors(
+    // VoxelWorld writes BlockChanged, so the reader cannot sit next to it
+    mut params: ParamSet<(MessageReader<BlockChanged>, VoxelWorld)>,
+    mut updates: MessageWriter<NeighborUpdate>,
+) {
+    let changes: Vec<BlockChanged> = params.p0().read().copied().collect();
+    let voxels = params.p1();
+
+    for change in changes {
+        for direction in Direction::ALL {
+            let pos = change.pos + direction.offset();
+            if pos == change.pos {
+                // Clamped at the top or bottom of the world
+                continue;
+            }
+            let Some(block) = voxels.get_state(pos) else {
+                continue;
+            };
+
+            updates.write(NeighborUpdate {
+                pos,
+                block,
+                direction: direction.opposite(),
+                change,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockId;
+    use crate::core::position::ChunkPos;
+    use crate::world::chunk::Chunk;
+    use crate::world::chunk_manager::ChunkManager;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_change_notifies_loaded_neighbors() {
+        let mut world = World::new();
+        world.init_resource::<Messages<BlockChanged>>();
+        world.init_resource::<Messages<NeighborUpdate>>();
+
+        let mut manager = ChunkManager::default();
+        let pos = ChunkPos::new(0, 0, 0);
+        manager.register_chunk(pos, world.spawn(Chunk::empty(pos, 0)).id());
+        world.insert_resource(manager);
+
+        // Corner block, so three neighbors are in unloaded chunks
+        let corner = BlockPos::new(0, 0, 0);
+        world
+            .run_system_once(move |mut voxels: VoxelWorld| {
+                voxels.set_block(corner, BlockId::STONE, BlockChangeCause::Player);
+                // Writing the same block again is not a change
+                voxels.set_block(corner, BlockId::STONE, BlockChangeCause::Player);
+            })
+            .unwrap();
+        world.run_system_once(notify_block_neighbors).unwrap();
+
+        let updates = world
+            .run_system_once(|mut updates: MessageReader<NeighborUpdate>| updates.read().copied().collect::<Vec<_>>())
+            .unwrap();
+
+        assert_eq!(updates.len(), 3);
+        for update in &updates {
+            assert_eq!(update.pos + update.direction.offset(), corner);
+            assert_eq!(update.change.old, BlockState::AIR);
+            assert_eq!(update.change.new.block(), BlockId::STONE);
+            assert_eq!(update.change.cause, BlockChangeCause::Player);
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use crate::core::block::BlockId;
+use crate::core::position::{BlockPos, ChunkPos, LocalPos, CHUNK_SIZE, CHUNK_SIZE_I32};
+use crate::world::chunk::Chunk;
+use bevy::prelude::*;
+use noise::{NoiseFn, Perlin};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// World Seed
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WorldSeed(pub u64);
+
+impl WorldSeed {
+    /// Noise libraries only take 32 bit seeds
+    pub fn as_u32(&self) -> u32 {
+        (self.0 ^ (self.0 >> 32)) as u32
+    }
+}
+
+/// Generation Stage Reached By A Chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GenerationStage {
+    Empty,
+    Shaped,
+    Carved,
+    Decorated,
+    Complete,
+}
+
+impl GenerationStage {
+    pub fn next(self) -> Option<Self> {
+        match self {
+            Self::Empty => Some(Self::Shaped),
+            Self::Shaped => Some(Self::Carved),
+            Self::Carved => Some(Self::Decorated),
+            Self::Decorated => Some(Self::Complete),
+            Self::Complete => None,
+        }
+    }
+
+    pub fn previous(self) -> Option<Self> {
+        match self {
+            Self::Empty => None,
+            Self::Shaped => Some(Self::Empty),
+            Self::Carved => Some(Self::Shaped),
+            Self::Decorated => Some(Self::Carved),
+            Self::Complete => Some(Self::Decorated),
+        }
+    }
+
+    /// Stage neighbors must reach before this stage may run
+    pub fn neighbor_requirement(self) -> Option<Self> {
+        match self {
+            // Terrain shape only depends on the seed
+            Self::Empty | Self::Shaped => None,
+            _ => self.previous(),
+        }
+    }
+}
+
+/// Read-Only Neighbor Chunks For A Generation Stage
+#[derive(Default, Clone)]
+pub struct ChunkNeighborhood {
+    chunks: HashMap<ChunkPos, Arc<Chunk>>,
+}
+
+impl ChunkNeighborhood {
+    pub fn insert(&mut self, chunk: Arc<Chunk>) {
+        self.chunks.insert(chunk.pos, chunk);
+    }
+
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&Chunk> {
+        self.chunks.get(&pos).map(|chunk| chunk.as_ref())
+    }
+
+    /// Full Resolution Block Lookup (None if not in the neighborhood)
+    pub fn get_block(&self, pos: BlockPos) -> Option<BlockId> {
+        self.chunk(pos.chunk_pos())
+            .map(|chunk| chunk.get_block(pos.local_pos()))
+    }
+}
+
+/// Terrain Generator
+pub trait WorldGenerator: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Terrain Shape (seed only, no neighbors)
+    fn shape_terrain(&self, chunk: &mut Chunk);
+
+    /// Caves and Ravines
+    fn carve(&self, _chunk: &mut Chunk, _neighbors: &ChunkNeighborhood) {}
+
+    /// Surface Features (trees, ores)
+    fn decorate(&self, _chunk: &mut Chunk, _neighbors: &ChunkNeighborhood) {}
+
+    /// Structures
+    fn place_structures(&self, _chunk: &mut Chunk, _neighbors: &ChunkNeighborhood) {}
+
+    /// Advance A Chunk By One Stage
+    fn run_stage(&self, chunk: &mut Chunk, neighbors: &ChunkNeighborhood) {
+        let Some(next) = chunk.stage.next() else {
+            return;
+        };
+
+        match next {
+            GenerationStage::Empty => unreachable!("no stage precedes Empty"),
+            GenerationStage::Shaped => self.shape_terrain(chunk),
+            GenerationStage::Carved => self.carve(chunk, neighbors),
+            GenerationStage::Decorated => self.decorate(chunk, neighbors),
+            GenerationStage::Complete => {
+                self.place_structures(chunk, neighbors);
+                chunk.compact();
+                // Generated data can always be rebuilt from the seed
+                chunk.mark_saved();
+            }
+        }
+        chunk.stage = next;
+    }
+
+    /// Generate Without Neighbors (tests, previews)
+    fn generate_chunk(&self, pos: ChunkPos, depth: u8) -> Chunk {
+        let mut chunk = Chunk::empty(pos, depth);
+        let neighbors = ChunkNeighborhood::default();
+        while chunk.stage != GenerationStage::Complete {
+            self.run_stage(&mut chunk, &neighbors);
+        }
+        chunk
+    }
+}
+
+/// Active Generator
+#[derive(Resource, Clone)]
+pub struct ActiveGenerator(pub Arc<dyn WorldGenerator>);
+
+impl ActiveGenerator {
+    pub fn new(generator: impl WorldGenerator + 'static) -> Self {
+        Self(Arc::new(generator))
+    }
+}
+
+impl FromWorld for ActiveGenerator {
+    fn from_world(world: &mut World) -> Self {
+        let seed = world.get_resource::<WorldSeed>().copied().unwrap_or_default();
+        Self::new(HeightmapGenerator::new(seed))
+    }
+}
+
+/// World Coordinates Of A Local Block (scaled by LOD)
+fn world_coords(chunk: &Chunk, x: u8, y: u8, z: u8) -> (i32, i32, i32) {
+    let scale = 1 << chunk.depth;
+    let origin = chunk.pos.to_world_pos();
+    (
+        origin.x + x as i32 * scale,
+        origin.y + y as i32 * scale,
+        origin.z + z as i32 * scale,
+    )
+}
+
+/// Block Lookup Across The Chunk Border
+fn block_at(chunk: &Chunk, neighbors: &ChunkNeighborhood, pos: BlockPos) -> Option<BlockId> {
+    if pos.chunk_pos() == chunk.pos {
+        Some(chunk.get_block(pos.local_pos()))
+    } else {
+        neighbors.get_block(pos)
+    }
+}
+
+/// Deterministic Per-Column Hash
+fn column_hash(seed: u64, x: i32, z: i32) -> u64 {
+    let mut h = seed ^ ((x as u32 as u64) << 32) ^ (z as u32 as u64);
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d049bb133111eb);
+    h ^ (h >> 31)
+}
+
+/// Perlin Heightmap Terrain (default)
+pub struct HeightmapGenerator {
+    seed: WorldSeed,
+    perlin: Perlin,
+    caves: Perlin,
+    pub frequency: f64,
+    pub amplitude: f64,
+    pub base_height: i32,
+    pub cave_frequency: f64,
+    pub cave_threshold: f64,
+    // Trees per 1000 grass columns
+    pub tree_density: u64,
+}
+
+impl HeightmapGenerator {
+    const TREE_RADIUS: i32 = 2;
+
+    pub fn new(seed: WorldSeed) -> Self {
+        Self {
+            seed,
+            perlin: Perlin::new(seed.as_u32()),
+            caves: Perlin::new(seed.as_u32().wrapping_add(1)),
+            frequency: 0.01,
+            amplitude: 100.0,
+            base_height: -80,
+            cave_frequency: 0.04,
+            cave_threshold: 0.08,
+            tree_density: 5,
+        }
+    }
+
+    pub fn height_at(&self, world_x: i32, world_z: i32) -> i32 {
+        let noise_value = self
+            .perlin
+            .get([world_x as f64 * self.frequency, world_z as f64 * self.frequency]);
+        ((noise_value + 1.0) * 0.5 * self.amplitude) as i32 + self.base_height
+    }
+
+    /// Topmost Grass In The Neighborhood Column
+    fn find_grass(&self, chunk: &Chunk, neighbors: &ChunkNeighborhood, x: i32, z: i32) -> Option<i32> {
+        let origin = chunk.pos.to_world_pos();
+        let top = origin.y + CHUNK_SIZE_I32 * 2 - 1;
+        let bottom = origin.y - CHUNK_SIZE_I32;
+
+        (bottom..=top)
+            .rev()
+            .find(|y| block_at(chunk, neighbors, BlockPos::new(x, *y, z)) == Some(BlockId::GRASS))
+    }
+
+    /// Write The Part Of A Tree Inside This Chunk
+    fn place_tree(&self, chunk: &mut Chunk, x: i32, base_y: i32, z: i32, trunk: i32) {
+        let radius = Self::TREE_RADIUS;
+        let top = base_y + trunk;
+
+        for y in top - 2..=top + 1 {
+            let layer_radius = if y > top - 1 { 1 } else { radius };
+            for dz in -layer_radius..=layer_radius {
+                for dx in -layer_radius..=layer_radius {
+                    self.set_if_inside(chunk, BlockPos::new(x + dx, y, z + dz), BlockId::LEAVES);
+                }
+            }
+        }
+
+        for y in base_y..top {
+            self.set_if_inside(chunk, BlockPos::new(x, y, z), BlockId::LOG);
+        }
+    }
+
+    fn set_if_inside(&self, chunk: &mut Chunk, pos: BlockPos, block: BlockId) {
+        if pos.chunk_pos() != chunk.pos {
+            return;
+        }
+
+        let local = pos.local_pos();
+        let existing = chunk.get_block(local);
+        // Leaves never replace terrain or trunks
+        if existing == BlockId::AIR || (block == BlockId::LOG && existing == BlockId::LEAVES) {
+            chunk.set_block(local, block);
+        }
+    }
+}
+
+impl WorldGenerator for HeightmapGenerator {
+    fn name(&self) -> &str {
+        "heightmap"
+    }
+
+    fn shape_terrain(&self, chunk: &mut Chunk) {
+        let scale = 1 << chunk.depth;
+
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let (world_x, _, world_z) = world_coords(chunk, x, 0, z);
+                let height = self.height_at(world_x, world_z);
+
+                for y in 0..CHUNK_SIZE {
+                    let (_, world_y, _) = world_coords(chunk, x, y, z);
+
+                    if world_y <= height - 3 {
+                        chunk.set_block(LocalPos::new(x, y, z), BlockId::STONE);
+                    } else if world_y < height {
+                        chunk.set_block(LocalPos::new(x, y, z), BlockId::DIRT);
+                    } else if world_y <= height + scale {
+                        chunk.set_block(LocalPos::new(x, y, z), BlockId::GRASS);
+                    }
+                }
+            }
+        }
+    }
+
+    fn carve(&self, chunk: &mut Chunk, _neighbors: &ChunkNeighborhood) {
+        // Coarse LOD voxels would turn thin caves into noise
+        if chunk.depth > 0 {
+            return;
+        }
+
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let (world_x, _, world_z) = world_coords(chunk, x, 0, z);
+                let height = self.height_at(world_x, world_z);
+
+                for y in 0..CHUNK_SIZE {
+                    let (_, world_y, _) = world_coords(chunk, x, y, z);
+                    // Keep a solid crust so caves rarely break the surface
+                    if world_y > height - 6 {
+                        break;
+                    }
+
+                    let noise_value = self.caves.get([
+                        world_x as f64 * self.cave_frequency,
+                        world_y as f64 * self.cave_frequency,
+                        world_z as f64 * self.cave_frequency,
+                    ]);
+                    if noise_value.abs() < self.cave_threshold {
+                        chunk.set_block(LocalPos::new(x, y, z), BlockId::AIR);
+                    }
+                }
+            }
+        }
+    }
+
+    fn decorate(&self, chunk: &mut Chunk, neighbors: &ChunkNeighborhood) {
+        if chunk.depth > 0 {
+            return;
+        }
+
+        let origin = chunk.pos.to_world_pos();
+        let radius = Self::TREE_RADIUS;
+
+        // Trees rooted in neighbor columns can still reach into this chunk
+        for world_z in origin.z - radius..origin.z + CHUNK_SIZE_I32 + radius {
+            for world_x in origin.x - radius..origin.x + CHUNK_SIZE_I32 + radius {
+                if column_hash(self.seed.0, world_x, world_z) % 1000 >= self.tree_density {
+                    continue;
+                }
+
+                let Some(ground) = self.find_grass(chunk, neighbors, world_x, world_z) else {
+                    continue;
+                };
+                let trunk = 4 + (column_hash(!self.seed.0, world_x, world_z) % 3) as i32;
+                self.place_tree(chunk, world_x, ground + 1, world_z, trunk);
+            }
+        }
+    }
+}
+
+/// Flat Terrain
+#[derive(Default)]
+pub struct FlatGenerator {
+    pub ground_height: i32,
+}
+
+impl WorldGenerator for FlatGenerator {
+    fn name(&self) -> &str {
+        "flat"
+    }
+
+    fn shape_terrain(&self, chunk: &mut Chunk) {
+        for y in 0..CHUNK_SIZE {
+            let (_, world_y, _) = world_coords(chunk, 0, y, 0);
+            let block = if world_y < self.ground_height - 3 {
+                BlockId::STONE
+            } else if world_y < self.ground_height {
+                BlockId::DIRT
+            } else if world_y == self.ground_height {
+                BlockId::GRASS
+            } else {
+                continue;
+            };
+
+            for z in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    chunk.set_block(LocalPos::new(x, y, z), block);
+                }
+            }
+        }
+    }
+}
+
+/// Debug Grid (pillars of every block type on a fixed spacing)
+pub struct DebugGridGenerator {
+    pub spacing: i32,
+    pub blocks: Vec<BlockId>,
+}
+
+impl Default for DebugGridGenerator {
+    fn default() -> Self {
+        Self {
+            spacing: 4,
+            blocks: vec![BlockId::STONE, BlockId::DIRT, BlockId::GRASS],
+        }
+    }
+}
+
+impl WorldGenerator for DebugGridGenerator {
+    fn name(&self) -> &str {
+        "debug_grid"
+    }
+
+    fn shape_terrain(&self, chunk: &mut Chunk) {
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let (world_x, _, world_z) = world_coords(chunk, x, 0, z);
+                if world_x.rem_euclid(self.spacing) != 0 || world_z.rem_euclid(self.spacing) != 0 {
+                    continue;
+                }
+
+                let cell = (world_x.div_euclid(self.spacing) + world_z.div_euclid(self.spacing))
+                    .rem_euclid(self.blocks.len() as i32);
+                let block = self.blocks[cell as usize];
+
+                for y in 0..CHUNK_SIZE {
+                    let (_, world_y, _) = world_coords(chunk, x, y, z);
+                    if world_y == 0 {
+                        chunk.set_block(LocalPos::new(x, y, z), block);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockState;
+
+    fn blocks(chunk: &Chunk) -> Vec<BlockState> {
+        chunk.blocks().iter().collect()
+    }
+
+    #[test]
+    fn test_seed_changes_terrain() {
+        let pos = ChunkPos::new(0, -2, 0);
+        let a = HeightmapGenerator::new(WorldSeed(1)).generate_chunk(pos, 0);
+        let b = HeightmapGenerator::new(WorldSeed(1)).generate_chunk(pos, 0);
+        let c = HeightmapGenerator::new(WorldSeed(2)).generate_chunk(pos, 0);
+
+        assert_eq!(blocks(&a), blocks(&b));
+        assert_ne!(blocks(&a), blocks(&c));
+    }
+
+    #[test]
+    fn test_generated_chunk_is_not_marked_for_saving() {
+        let chunk = FlatGenerator::default().generate_chunk(ChunkPos::new(0, 0, 0), 0);
+        assert!(!chunk.needs_save());
+        assert_eq!(chunk.get_block(LocalPos::new(4, 0, 4)), BlockId::GRASS);
+        assert_eq!(chunk.get_block(LocalPos::new(4, 1, 4)), BlockId::AIR);
+    }
+}
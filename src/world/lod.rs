@@ -0,0 +1,343 @@
+use crate::core::block::{BlockId, BlockState};
+use crate::core::position::{ChunkPos, LocalPos, CHUNK_SIZE};
+use crate::world::chunk::{Chunk, CHUNK_VOLUME};
+use crate::world::chunk_manager::ChunkManager;
+use crate::world::octree::Octree;
+use crate::world::palette::PalettedStorage;
+use crate::world::pipeline::ChunkKey;
+use crate::world::region::{save_or_warn, RegionStorage};
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Downsampled LOD Chunk Cache
+///
+/// Coarse chunks built from finer chunk data are written to one region
+/// directory per depth, so edits stay visible from far away and every LOD
+/// agrees with the one below it.
+#[derive(Resource)]
+pub struct LodCache {
+    root: PathBuf,
+    // Ancestors of edited chunks, rebuilt on the next merge
+    stale: HashSet<ChunkKey>,
+}
+
+impl Default for LodCache {
+    fn default() -> Self {
+        Self::new("saves/world/lod")
+    }
+}
+
+impl LodCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            stale: HashSet::new(),
+        }
+    }
+
+    pub fn storage(&self, depth: u8) -> RegionStorage {
+        RegionStorage::new(self.root.join(format!("depth{}", depth)))
+    }
+
+    /// Where A Coarse Chunk Can Be Loaded From (None if it must be regenerated)
+    pub fn source(&self, key: ChunkKey) -> Option<RegionStorage> {
+        (key.depth > 0 && !self.stale.contains(&key)).then(|| self.storage(key.depth))
+    }
+
+    pub fn store(&mut self, chunk: &Chunk) {
+        // A failed write leaves the cache entry stale, so it gets rebuilt
+        if save_or_warn(&self.storage(chunk.depth), chunk).is_ok() {
+            self.stale.remove(&ChunkKey::new(chunk.pos, chunk.depth));
+        }
+    }
+
+    pub fn mark_stale(&mut self, key: ChunkKey) {
+        if key.depth > 0 {
+            self.stale.insert(key);
+        }
+    }
+
+    /// Build A Coarse Chunk From Loaded Chunks, Falling Back To Saved Data
+    ///
+    /// Like `build_from_loaded`, but levels that are not loaded come from
+    /// the cache (unless stale) and full resolution chunks from their
+    /// region files.
+    pub fn rebuild<'a>(
+        &self,
+        key: ChunkKey,
+        lookup: &impl Fn(ChunkKey) -> Option<&'a Chunk>,
+        region_storage: &RegionStorage,
+    ) -> Option<Chunk> {
+        if !self.stale.contains(&key) {
+            if let Some(chunk) = lookup(key) {
+                return Some(chunk.clone());
+            }
+            let saved = if key.depth == 0 { Some(region_storage.clone()) } else { self.source(key) };
+            if let Some(chunk) = saved.and_then(|storage| storage.load_chunk(key.pos).ok().flatten()) {
+                return Some(chunk);
+            }
+        }
+
+        let children = child_keys(key)?;
+        let mut built = Vec::with_capacity(8);
+        for child in children {
+            built.push(self.rebuild(child, lookup, region_storage)?);
+        }
+        let refs: [&Chunk; 8] = std::array::from_fn(|i| &built[i]);
+        Some(downsample(key, refs))
+    }
+}
+
+/// The Eight Half-Size Chunks Making Up A Coarse Chunk (x, then y, then z bit)
+pub fn child_keys(key: ChunkKey) -> Option<[ChunkKey; 8]> {
+    let depth = key.depth.checked_sub(1)?;
+    let half = 1 << depth;
+    Some(std::array::from_fn(|octant| {
+        let offset = |bit: usize| if octant & (1 << bit) != 0 { half } else { 0 };
+        ChunkKey::new(
+            ChunkPos::new(key.pos.x + offset(0), key.pos.y + offset(1), key.pos.z + offset(2)),
+            depth,
+        )
+    }))
+}
+
+/// Halve The Resolution Of Eight Child Chunks
+///
+/// A coarse voxel is solid when at least half of its 2x2x2 children are,
+/// so one block thick floors and walls survive. Its block is the most
+/// common one in the upper layer, which keeps grass on top of hills.
+pub fn downsample(key: ChunkKey, children: [&Chunk; 8]) -> Chunk {
+    let half = CHUNK_SIZE / 2;
+    let mut blocks = PalettedStorage::new(CHUNK_VOLUME, BlockState::AIR);
+
+    // Uniform children downsample to themselves
+    if let Some(state) = children[0].uniform_state()
+        && children.iter().all(|child| child.uniform_state() == Some(state))
+    {
+        return Chunk::from_blocks(key.pos, key.depth, PalettedStorage::new(CHUNK_VOLUME, state));
+    }
+
+    for z in 0..CHUNK_SIZE {
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let octant = (x / half) as usize | ((y / half) as usize) << 1 | ((z / half) as usize) << 2;
+                let child = children[octant];
+                let base = [(x % half) * 2, (y % half) * 2, (z % half) * 2];
+
+                let mut solid = 0;
+                let mut upper: Vec<BlockState> = Vec::with_capacity(4);
+                let mut lower: Vec<BlockState> = Vec::with_capacity(4);
+                for dy in 0..2 {
+                    for dz in 0..2 {
+                        for dx in 0..2 {
+                            let state = child.get_state(LocalPos::new(base[0] + dx, base[1] + dy, base[2] + dz));
+                            if state.block() == BlockId::AIR {
+                                continue;
+                            }
+                            solid += 1;
+                            if dy == 1 { upper.push(state) } else { lower.push(state) }
+                        }
+                    }
+                }
+
+                if solid >= 4 {
+                    let layer = if upper.is_empty() { &lower } else { &upper };
+                    let state = most_common(layer);
+                    blocks.set(LocalPos::new(x, y, z).to_index(), state);
+                }
+            }
+        }
+    }
+
+    blocks.compact();
+    Chunk::from_blocks(key.pos, key.depth, blocks)
+}
+
+fn most_common(states: &[BlockState]) -> BlockState {
+    // Earlier entries win ties
+    let mut best = (0, BlockState::AIR);
+    for state in states {
+        let count = states.iter().filter(|other| *other == state).count();
+        if count > best.0 {
+            best = (count, *state);
+        }
+    }
+    best.1
+}
+
+/// Build A Coarse Chunk From Finer Chunks That Are Still Loaded
+///
+/// `lookup` returns the loaded chunk for a key, if it is loaded at exactly
+/// that LOD. Levels in between are downsampled recursively.
+pub fn build_from_loaded<'a>(key: ChunkKey, lookup: &impl Fn(ChunkKey) -> Option<&'a Chunk>) -> Option<Chunk> {
+    if let Some(chunk) = lookup(key) {
+        return Some(chunk.clone());
+    }
+
+    let children = child_keys(key)?;
+    let mut built = Vec::with_capacity(8);
+    for child in children {
+        built.push(build_from_loaded(child, lookup)?);
+    }
+    let refs: [&Chunk; 8] = std::array::from_fn(|i| &built[i]);
+    Some(downsample(key, refs))
+}
+
+/// Invalidate Cached Ancestors Of Edited Chunks
+pub fn invalidate_edited_lods(
+    mut lod_cache: ResMut<LodCache>,
+    octree: Res<Octree>,
+    query: Query<&Chunk, Changed<Chunk>>,
+) {
+    let size = CHUNK_SIZE as f32;
+    for chunk in query.iter().filter(|chunk| chunk.depth == 0 && chunk.needs_save()) {
+        let center = Vec3::new(chunk.pos.x as f32, chunk.pos.y as f32, chunk.pos.z as f32) * size + Vec3::splat(size / 2.0);
+
+        for node in octree.path_to(center) {
+            let lod = (node.size / size).log2() as u8;
+            let node_min = node.center - Vec3::splat(node.size / 2.0);
+            lod_cache.mark_stale(ChunkKey::new(ChunkPos::from_world_pos(node_min), lod));
+        }
+    }
+}
+
+/// Rebuild Stale Cache Entries On Exit So The Next Session Sees Every Edit
+///
+/// Entries that cannot be rebuilt (some of their chunks were never saved)
+/// are dropped instead, so the next session regenerates them.
+pub fn flush_stale_lods_on_exit(
+    mut exit_events: MessageReader<AppExit>,
+    mut lod_cache: ResMut<LodCache>,
+    chunk_manager: Res<ChunkManager>,
+    region_storage: Res<RegionStorage>,
+    chunks: Query<&Chunk>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+
+    let lookup = |key: ChunkKey| {
+        chunk_manager
+            .get_chunk_entity(key.pos)
+            .and_then(|entity| chunks.get(entity).ok())
+            .filter(|chunk| chunk.depth == key.depth)
+    };
+
+    // Finer levels first, so coarser ones find them in the cache
+    let mut stale: Vec<ChunkKey> = lod_cache.stale.iter().copied().collect();
+    stale.sort_by_key(|key| key.depth);
+    for key in stale {
+        let storage = lod_cache.storage(key.depth);
+        match lod_cache.rebuild(key, &lookup, &region_storage) {
+            Some(chunk) if save_or_warn(&storage, &chunk).is_ok() => {
+                lod_cache.stale.remove(&key);
+            }
+            _ => {
+                if let Err(err) = storage.remove_chunk(key.pos) {
+                    warn!("Failed to drop stale LOD chunk at {:?}: {}", key.pos, err);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn filled(key: ChunkKey, height: u8) -> Chunk {
+        let mut chunk = Chunk::empty(key.pos, key.depth);
+        for z in 0..CHUNK_SIZE {
+            for y in 0..height {
+                for x in 0..CHUNK_SIZE {
+                    let block = if y + 1 == height { BlockId::GRASS } else { BlockId::STONE };
+                    chunk.set_block(LocalPos::new(x, y, z), block);
+                }
+            }
+        }
+        chunk
+    }
+
+    #[test]
+    fn test_downsample_preserves_surface() {
+        let key = ChunkKey::new(ChunkPos::new(0, 0, 0), 1);
+        let children = child_keys(key).unwrap().map(|child| {
+            // Upper children are empty air
+            if child.pos.y > 0 { Chunk::empty(child.pos, 0) } else { filled(child, 8) }
+        });
+        let refs: [&Chunk; 8] = std::array::from_fn(|i| &children[i]);
+        let coarse = downsample(key, refs);
+
+        assert_eq!(coarse.depth, 1);
+        assert_eq!(coarse.get_block(LocalPos::new(0, 0, 0)), BlockId::STONE);
+        // Fine y = 6..8 holds stone under grass, grass wins the upper layer
+        assert_eq!(coarse.get_block(LocalPos::new(5, 3, 9)), BlockId::GRASS);
+        assert_eq!(coarse.get_block(LocalPos::new(5, 4, 9)), BlockId::AIR);
+        assert_eq!(coarse.get_block(LocalPos::new(20, 20, 20)), BlockId::AIR);
+    }
+
+    #[test]
+    fn test_exit_rebuilds_stale_entries() {
+        let root = std::env::temp_dir().join(format!("aeternitas_lod_exit_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let region_storage = RegionStorage::new(root.join("regions"));
+        let key = ChunkKey::new(ChunkPos::new(0, 0, 0), 1);
+        let edited = ChunkKey::new(ChunkPos::new(1, 0, 0), 0);
+        let saved = ChunkKey::new(ChunkPos::new(0, 0, 1), 0);
+
+        // Outdated cache entry from before the edit
+        let mut lod_cache = LodCache::new(root.join("lod"));
+        lod_cache.store(&Chunk::empty(key.pos, 1));
+        lod_cache.mark_stale(key);
+
+        // One edited child is still loaded, another was saved and unloaded
+        let mut world = World::new();
+        let mut chunk_manager = ChunkManager::default();
+        for child in child_keys(key).unwrap() {
+            let chunk = if child == edited || child == saved { filled(child, 8) } else { Chunk::empty(child.pos, 0) };
+            if child == saved {
+                region_storage.save_chunk(&chunk).unwrap();
+            } else {
+                chunk_manager.register_chunk(child.pos, world.spawn(chunk).id());
+            }
+        }
+        world.insert_resource(chunk_manager);
+        world.insert_resource(region_storage);
+        world.insert_resource(lod_cache);
+        world.init_resource::<Messages<AppExit>>();
+        world.write_message(AppExit::Success);
+        world.run_system_once(flush_stale_lods_on_exit).unwrap();
+
+        // The next session loads the rebuilt chunk instead of regenerating it
+        let next_session = LodCache::new(root.join("lod"));
+        let coarse = next_session.source(key).unwrap().load_chunk(key.pos).unwrap().unwrap();
+        assert_eq!(coarse.get_block(LocalPos::new(20, 3, 5)), BlockId::GRASS);
+        assert_eq!(coarse.get_block(LocalPos::new(5, 3, 20)), BlockId::GRASS);
+        assert_eq!(coarse.get_block(LocalPos::new(5, 3, 5)), BlockId::AIR);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_build_from_loaded_recurses_through_levels() {
+        let key = ChunkKey::new(ChunkPos::new(0, 0, 0), 2);
+        let mut leaves = Vec::new();
+        for level_one in child_keys(key).unwrap() {
+            leaves.extend(child_keys(level_one).unwrap().map(|leaf| filled(leaf, 3)));
+        }
+        let lookup = |key: ChunkKey| leaves.iter().find(|chunk| chunk.pos == key.pos && chunk.depth == key.depth);
+
+        let coarse = build_from_loaded(key, &lookup).unwrap();
+        assert_eq!(coarse.depth, 2);
+        // Three fine layers collapse to one solid layer with grass on top
+        assert_eq!(coarse.get_block(LocalPos::new(0, 0, 0)), BlockId::GRASS);
+        assert_eq!(coarse.get_block(LocalPos::new(0, 1, 0)), BlockId::AIR);
+
+        // A missing leaf means the level cannot be built
+        let partial = |key: ChunkKey| lookup(key).filter(|chunk| chunk.pos != ChunkPos::new(3, 3, 3));
+        assert!(build_from_loaded(key, &partial).is_none());
+    }
+}
@@ -1,4 +1,10 @@
 pub mod chunk_manager;
 pub mod chunk;
+pub mod events;
 pub mod generation;
-pub mod octree;
\ No newline at end of file
+pub mod lod;
+pub mod octree;
+pub mod palette;
+pub mod pipeline;
+pub mod region;
+pub mod voxel_world;
@@ -29,6 +29,24 @@ impl Octree {
         self.root.subdivide_if_needed(player_pos, self.min_size, self.max_depth);
     }
 
+    /// Nodes containing a point, from the root down to its leaf
+    pub fn path_to(&self, point: Vec3) -> Vec<&OctreeNode> {
+        let mut path = Vec::new();
+        let mut node = &self.root;
+        while node.contains(point) {
+            path.push(node);
+            let Some(child) = node
+                .children
+                .as_ref()
+                .and_then(|children| children.iter().find(|child| child.contains(point)))
+            else {
+                break;
+            };
+            node = child;
+        }
+        path
+    }
+
     /// Collect all leaf nodes (final chunks)
     pub fn collect_leaves(&self) -> Vec<&OctreeNode> {
         let mut result = Vec::new();
@@ -46,6 +64,13 @@ impl OctreeNode {
         }
     }
 
+    /// Minimum corner inclusive, maximum corner exclusive
+    pub fn contains(&self, point: Vec3) -> bool {
+        let min = self.center - Vec3::splat(self.size / 2.0);
+        let max = self.center + Vec3::splat(self.size / 2.0);
+        point.cmpge(min).all() && point.cmplt(max).all()
+    }
+
     fn subdivide_if_needed(&mut self, player_pos: Vec3, min_size: f32, depth: u8) {
 
         // Smallest chunk reached
@@ -65,6 +90,9 @@ impl OctreeNode {
                     child.subdivide_if_needed(player_pos, min_size, depth - 1);
                 }
             }
+        } else {
+            // Merge back once the player moved away
+            self.children = None;
         }
     }
 
@@ -0,0 +1,254 @@
+use crate::core::block::BlockState;
+
+/// Palette-Indexed Block State Storage
+///
+/// Every voxel stores an index into `palette` packed into `bits_per_entry`
+/// bits. Entries never straddle two words. A storage with a single palette
+/// entry uses zero bits and keeps no data at all (uniform chunks).
+#[derive(Debug, Clone)]
+pub struct PalettedStorage {
+    len: usize,
+    palette: Vec<BlockState>,
+    bits_per_entry: u8,
+    data: Vec<u64>,
+}
+
+impl PalettedStorage {
+    /// Uniform Storage
+    pub fn new(len: usize, fill: BlockState) -> Self {
+        Self {
+            len,
+            palette: vec![fill],
+            bits_per_entry: 0,
+            data: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn palette(&self) -> &[BlockState] {
+        &self.palette
+    }
+
+    pub fn bits_per_entry(&self) -> u8 {
+        self.bits_per_entry
+    }
+
+    /// Single-Value Fast Path
+    pub fn single_value(&self) -> Option<BlockState> {
+        if self.bits_per_entry == 0 {
+            Some(self.palette[0])
+        } else {
+            None
+        }
+    }
+
+    /// Heap Bytes Used By Voxel Data
+    pub fn memory_usage(&self) -> usize {
+        self.palette.len() * std::mem::size_of::<BlockState>()
+            + self.data.len() * std::mem::size_of::<u64>()
+    }
+
+    pub fn get(&self, index: usize) -> BlockState {
+        debug_assert!(index < self.len);
+        if self.bits_per_entry == 0 {
+            return self.palette[0];
+        }
+        self.palette[self.read_index(index)]
+    }
+
+    pub fn set(&mut self, index: usize, state: BlockState) {
+        debug_assert!(index < self.len);
+        if self.bits_per_entry == 0 && self.palette[0] == state {
+            return;
+        }
+
+        let palette_index = match self.palette.iter().position(|id| *id == state) {
+            Some(existing) => existing,
+            None => {
+                self.palette.push(state);
+                let needed = Self::bits_for(self.palette.len());
+                if needed > self.bits_per_entry {
+                    self.resize(needed);
+                }
+                self.palette.len() - 1
+            }
+        };
+
+        self.write_index(index, palette_index);
+    }
+
+    /// Iterate All Entries In Index Order
+    pub fn iter(&self) -> impl Iterator<Item = BlockState> + '_ {
+        (0..self.len).map(move |index| self.get(index))
+    }
+
+    /// Drop Unused Palette Entries and Shrink Bit Width
+    pub fn compact(&mut self) {
+        if self.bits_per_entry == 0 {
+            return;
+        }
+
+        let mut used = vec![false; self.palette.len()];
+        for index in 0..self.len {
+            used[self.read_index(index)] = true;
+        }
+
+        if used.iter().all(|u| *u) {
+            return;
+        }
+
+        let values: Vec<BlockState> = self.iter().collect();
+        let mut compacted = Self::new(self.len, values[0]);
+        for (index, state) in values.into_iter().enumerate() {
+            compacted.set(index, state);
+        }
+        *self = compacted;
+    }
+
+    /// Rebuild From Raw Parts (used by region files)
+    pub fn from_parts(len: usize, palette: Vec<BlockState>, bits_per_entry: u8, data: Vec<u64>) -> Option<Self> {
+        if palette.is_empty() || bits_per_entry > 16 {
+            return None;
+        }
+        if bits_per_entry == 0 {
+            return (palette.len() == 1 && data.is_empty()).then_some(Self {
+                len,
+                palette,
+                bits_per_entry,
+                data,
+            });
+        }
+        if palette.len() > 1 << bits_per_entry || data.len() != Self::words_for(len, bits_per_entry) {
+            return None;
+        }
+
+        let storage = Self {
+            len,
+            palette,
+            bits_per_entry,
+            data,
+        };
+        if (0..len).any(|index| storage.read_index(index) >= storage.palette.len()) {
+            return None;
+        }
+        Some(storage)
+    }
+
+    pub fn data(&self) -> &[u64] {
+        &self.data
+    }
+
+    fn bits_for(palette_len: usize) -> u8 {
+        if palette_len <= 1 {
+            0
+        } else {
+            (usize::BITS - (palette_len - 1).leading_zeros()) as u8
+        }
+    }
+
+    fn entries_per_word(bits: u8) -> usize {
+        64 / bits as usize
+    }
+
+    fn words_for(len: usize, bits: u8) -> usize {
+        len.div_ceil(Self::entries_per_word(bits))
+    }
+
+    fn read_index(&self, index: usize) -> usize {
+        let per_word = Self::entries_per_word(self.bits_per_entry);
+        let word = self.data[index / per_word];
+        let shift = (index % per_word) * self.bits_per_entry as usize;
+        let mask = (1u64 << self.bits_per_entry) - 1;
+        ((word >> shift) & mask) as usize
+    }
+
+    fn write_index(&mut self, index: usize, palette_index: usize) {
+        let per_word = Self::entries_per_word(self.bits_per_entry);
+        let shift = (index % per_word) * self.bits_per_entry as usize;
+        let mask = (1u64 << self.bits_per_entry) - 1;
+        let word = &mut self.data[index / per_word];
+        *word = (*word & !(mask << shift)) | ((palette_index as u64 & mask) << shift);
+    }
+
+    /// Repack Data With A Wider Bit Width
+    fn resize(&mut self, bits: u8) {
+        let old_bits = self.bits_per_entry;
+        let old_data = std::mem::take(&mut self.data);
+
+        self.bits_per_entry = bits;
+        self.data = vec![0; Self::words_for(self.len, bits)];
+
+        // Uniform storage was implicitly all palette index 0
+        if old_bits == 0 {
+            return;
+        }
+
+        let old = Self {
+            len: self.len,
+            palette: Vec::new(),
+            bits_per_entry: old_bits,
+            data: old_data,
+        };
+        for index in 0..self.len {
+            let palette_index = old.read_index(index);
+            self.write_index(index, palette_index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockId;
+
+    const LEN: usize = 32 * 32 * 32;
+
+    #[test]
+    fn test_uniform_storage_has_no_data() {
+        let storage = PalettedStorage::new(LEN, BlockState::from(BlockId::STONE));
+        assert_eq!(storage.single_value(), Some(BlockState::from(BlockId::STONE)));
+        assert_eq!(storage.get(1234), BlockState::from(BlockId::STONE));
+        assert!(storage.data().is_empty());
+    }
+
+    #[test]
+    fn test_set_grows_bit_width() {
+        let mut storage = PalettedStorage::new(LEN, BlockState::from(BlockId::AIR));
+        storage.set(0, BlockState::from(BlockId::STONE));
+        assert_eq!(storage.bits_per_entry(), 1);
+
+        storage.set(1, BlockState::from(BlockId::DIRT));
+        storage.set(2, BlockState::from(BlockId::GRASS));
+        assert_eq!(storage.bits_per_entry(), 2);
+
+        for extra in 10..30 {
+            storage.set(extra as usize, BlockState(extra));
+        }
+        assert_eq!(storage.bits_per_entry(), 5);
+
+        assert_eq!(storage.get(0), BlockState::from(BlockId::STONE));
+        assert_eq!(storage.get(1), BlockState::from(BlockId::DIRT));
+        assert_eq!(storage.get(2), BlockState::from(BlockId::GRASS));
+        assert_eq!(storage.get(15), BlockState(15));
+        assert_eq!(storage.get(LEN - 1), BlockState::from(BlockId::AIR));
+    }
+
+    #[test]
+    fn test_compact_restores_single_value() {
+        let mut storage = PalettedStorage::new(LEN, BlockState::from(BlockId::AIR));
+        for index in 0..LEN {
+            storage.set(index, BlockState::from(BlockId::STONE));
+        }
+        assert!(storage.single_value().is_none());
+
+        storage.compact();
+        assert_eq!(storage.single_value(), Some(BlockState::from(BlockId::STONE)));
+    }
+}
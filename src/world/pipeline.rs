@@ -0,0 +1,411 @@
+use crate::core::position::ChunkPos;
+use crate::world::chunk::Chunk;
+use crate::world::generation::{ChunkNeighborhood, GenerationStage, WorldGenerator};
+use crate::world::lod::LodCache;
+use crate::world::region::RegionStorage;
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Chunk Identity Inside The Pipeline (same position can exist per LOD)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkKey {
+    pub pos: ChunkPos,
+    pub depth: u8,
+}
+
+impl ChunkKey {
+    pub fn new(pos: ChunkPos, depth: u8) -> Self {
+        Self { pos, depth }
+    }
+
+    /// Surrounding Chunks At The Same LOD (3x3x3 minus self)
+    pub fn neighbors(&self) -> Vec<ChunkKey> {
+        // Coarse LOD voxels are larger than any cross-border feature
+        if self.depth > 0 {
+            return Vec::new();
+        }
+
+        let mut keys = Vec::with_capacity(26);
+        for dy in -1..=1 {
+            for dz in -1..=1 {
+                for dx in -1..=1 {
+                    let pos = ChunkPos::new(self.pos.x + dx, self.pos.y + dy, self.pos.z + dz);
+                    let key = ChunkKey::new(pos, self.depth);
+                    // Clamped world edges fold back onto existing chunks
+                    if key != *self && !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+        keys
+    }
+}
+
+/// Staged Chunk Generation
+///
+/// Requested chunks are driven to `Complete`. Every stage that depends on
+/// neighbors pulls the surrounding chunks into the pipeline as padding and
+/// only runs once they reached the stage before it. Loading and every stage
+/// run as tasks on the `AsyncComputeTaskPool`.
+#[derive(Resource)]
+pub struct GenerationPipeline {
+    chunks: HashMap<ChunkKey, Arc<Chunk>>,
+    requested: HashSet<ChunkKey>,
+    required: HashMap<ChunkKey, GenerationStage>,
+    requirements_dirty: bool,
+    running: HashMap<ChunkKey, Task<Chunk>>,
+    pub max_tasks: usize,
+}
+
+impl Default for GenerationPipeline {
+    fn default() -> Self {
+        Self {
+            chunks: HashMap::new(),
+            requested: HashSet::new(),
+            required: HashMap::new(),
+            requirements_dirty: false,
+            running: HashMap::new(),
+            max_tasks: 32,
+        }
+    }
+}
+
+impl GenerationPipeline {
+    pub fn request(&mut self, key: ChunkKey) {
+        if self.requested.insert(key) {
+            self.requirements_dirty = true;
+        }
+    }
+
+    pub fn cancel(&mut self, key: ChunkKey) {
+        if self.requested.remove(&key) {
+            self.requirements_dirty = true;
+        }
+    }
+
+    pub fn is_requested(&self, key: ChunkKey) -> bool {
+        self.requested.contains(&key)
+    }
+
+    pub fn requested(&self) -> impl Iterator<Item = &ChunkKey> {
+        self.requested.iter()
+    }
+
+    pub fn stage_of(&self, key: ChunkKey) -> Option<GenerationStage> {
+        self.chunks.get(&key).map(|chunk| chunk.stage)
+    }
+
+    /// Chunks In Flight (requested and padding)
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty() && self.running.is_empty()
+    }
+
+    pub fn running_tasks(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Propagate Stage Requirements Outward From Requested Chunks
+    ///
+    /// Only chunks that are loaded and still below their stage pull in
+    /// padding. Saved chunks load `Complete`, so revisiting them never
+    /// generates their surroundings.
+    fn update_requirements(&mut self) {
+        self.required.clear();
+
+        let mut queue: Vec<(ChunkKey, GenerationStage)> = self
+            .requested
+            .iter()
+            .map(|key| (*key, GenerationStage::Complete))
+            .collect();
+
+        while let Some((key, stage)) = queue.pop() {
+            if self.required.get(&key).is_some_and(|existing| *existing >= stage) {
+                continue;
+            }
+            self.required.insert(key, stage);
+
+            // Unknown until loaded, recomputed once the load finishes
+            let generating = self.chunks.get(&key).is_some_and(|chunk| chunk.stage < stage);
+            // The most demanding stage decides what the neighbors need
+            if generating && let Some(needed) = stage.neighbor_requirement() {
+                for neighbor in key.neighbors() {
+                    queue.push((neighbor, needed));
+                }
+            }
+        }
+
+        // Drop padding nobody depends on anymore (dropping a task cancels it)
+        let required = &self.required;
+        self.chunks.retain(|key, _| required.contains_key(key));
+        self.running.retain(|key, _| required.contains_key(key));
+        self.requirements_dirty = false;
+    }
+
+    /// Whether The Next Stage Of A Chunk May Run
+    fn is_ready(&self, key: ChunkKey, stage: GenerationStage) -> bool {
+        let Some(next) = stage.next() else {
+            return false;
+        };
+        let Some(needed) = next.neighbor_requirement() else {
+            return true;
+        };
+
+        key.neighbors().iter().all(|neighbor| {
+            self.chunks
+                .get(neighbor)
+                .is_some_and(|chunk| chunk.stage >= needed)
+        })
+    }
+
+    fn neighborhood(&self, key: ChunkKey) -> ChunkNeighborhood {
+        let mut neighborhood = ChunkNeighborhood::default();
+        for neighbor in key.neighbors() {
+            if let Some(chunk) = self.chunks.get(&neighbor) {
+                neighborhood.insert(chunk.clone());
+            }
+        }
+        neighborhood
+    }
+
+    /// Saved and Cached Chunks Skip Generation Entirely
+    fn create_chunk(storage: Option<&RegionStorage>, key: ChunkKey) -> Chunk {
+        if let Some(storage) = storage {
+            match storage.load_chunk(key.pos) {
+                Ok(Some(chunk)) if chunk.depth == key.depth => return chunk,
+                Ok(_) => {}
+                Err(err) => warn!("Failed to load chunk at {:?}, regenerating: {}", key.pos, err),
+            }
+        }
+        Chunk::empty(key.pos, key.depth)
+    }
+
+    /// Collect Finished Tasks and Start New Ones, Returning Finished Requested Chunks
+    pub fn advance(
+        &mut self,
+        generator: &Arc<dyn WorldGenerator>,
+        region_storage: &RegionStorage,
+        lod_cache: &LodCache,
+    ) -> Vec<Chunk> {
+        let done: Vec<(ChunkKey, Chunk)> = self
+            .running
+            .iter_mut()
+            .filter_map(|(key, task)| check_ready(task).map(|chunk| (*key, chunk)))
+            .collect();
+        for (key, chunk) in done {
+            self.running.remove(&key);
+            // A freshly loaded chunk may need padding after all
+            if self.chunks.insert(key, Arc::new(chunk)).is_none() {
+                self.requirements_dirty = true;
+            }
+        }
+        if self.requirements_dirty {
+            self.update_requirements();
+        }
+
+        self.spawn_tasks(generator, region_storage, lod_cache);
+
+        let finished: Vec<ChunkKey> = self
+            .requested
+            .iter()
+            .filter(|key| self.stage_of(**key) == Some(GenerationStage::Complete))
+            .copied()
+            .collect();
+
+        finished
+            .into_iter()
+            .map(|key| {
+                self.cancel(key);
+                // Keep a copy while neighbors still need it as padding
+                (*self.chunks[&key]).clone()
+            })
+            .collect()
+    }
+
+    fn spawn_tasks(&mut self, generator: &Arc<dyn WorldGenerator>, region_storage: &RegionStorage, lod_cache: &LodCache) {
+        if self.running.len() >= self.max_tasks {
+            return;
+        }
+
+        // Lowest stages first so padding unblocks requested chunks sooner
+        let mut work: Vec<(ChunkKey, Option<GenerationStage>)> = self
+            .required
+            .iter()
+            .filter(|(key, _)| !self.running.contains_key(key))
+            .filter_map(|(key, required)| match self.stage_of(*key) {
+                None => Some((*key, None)),
+                Some(stage) if stage < *required && self.is_ready(*key, stage) => Some((*key, Some(stage))),
+                Some(_) => None,
+            })
+            .collect();
+        work.sort_by_key(|(_, stage)| *stage);
+
+        let pool = AsyncComputeTaskPool::get();
+        for (key, stage) in work {
+            if self.running.len() >= self.max_tasks {
+                break;
+            }
+
+            let task = match stage {
+                None => {
+                    // Coarse chunks come from downsampled finer data when cached
+                    let storage = match key.depth {
+                        0 => Some(region_storage.clone()),
+                        _ => lod_cache.source(key),
+                    };
+                    pool.spawn(async move { Self::create_chunk(storage.as_ref(), key) })
+                }
+                Some(_) => {
+                    let generator = generator.clone();
+                    let neighborhood = self.neighborhood(key);
+                    // The pipeline keeps its copy so neighbors can still read it
+                    let chunk = self.chunks[&key].clone();
+                    pool.spawn(async move {
+                        let mut chunk = Arc::unwrap_or_clone(chunk);
+                        generator.run_stage(&mut chunk, &neighborhood);
+                        chunk
+                    })
+                }
+            };
+            self.running.insert(key, task);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::BlockId;
+    use crate::core::position::{LocalPos, CHUNK_SIZE};
+    use crate::world::generation::{HeightmapGenerator, WorldSeed};
+    use bevy::tasks::TaskPool;
+    use std::time::Duration;
+
+    fn empty_storage() -> RegionStorage {
+        RegionStorage::new(std::env::temp_dir().join("aeternitas_pipeline_unused"))
+    }
+
+    fn empty_cache() -> LodCache {
+        LodCache::new(std::env::temp_dir().join("aeternitas_pipeline_unused_lod"))
+    }
+
+    fn heightmap(seed: u64) -> Arc<dyn WorldGenerator> {
+        AsyncComputeTaskPool::get_or_init(TaskPool::default);
+        Arc::new(HeightmapGenerator::new(WorldSeed(seed)))
+    }
+
+    fn run_until_done(pipeline: &mut GenerationPipeline, generator: &Arc<dyn WorldGenerator>) -> Vec<Chunk> {
+        let storage = empty_storage();
+        let cache = empty_cache();
+        let mut finished = Vec::new();
+        for _ in 0..100_000 {
+            finished.extend(pipeline.advance(generator, &storage, &cache));
+            if pipeline.requested().next().is_none() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        finished
+    }
+
+    #[test]
+    fn test_neighbors_reach_previous_stage() {
+        let generator = heightmap(7);
+        let mut pipeline = GenerationPipeline::default();
+        let key = ChunkKey::new(ChunkPos::new(0, -2, 0), 0);
+        pipeline.request(key);
+
+        let finished = run_until_done(&mut pipeline, &generator);
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].stage, GenerationStage::Complete);
+
+        // Padding is kept only while a requested chunk needs it
+        pipeline.advance(&generator, &empty_storage(), &empty_cache());
+        assert!(pipeline.is_empty());
+    }
+
+    #[test]
+    fn test_trees_match_across_chunk_borders() {
+        let generator = heightmap(2);
+        let mut pipeline = GenerationPipeline::default();
+
+        // Sweep a row of surface chunks so at least one tree straddles a border
+        let keys: Vec<ChunkKey> = (0..3)
+            .map(|x| ChunkKey::new(ChunkPos::new(x, -1, 0), 0))
+            .collect();
+        for key in &keys {
+            pipeline.request(*key);
+        }
+        let mut finished = run_until_done(&mut pipeline, &generator);
+        assert_eq!(finished.len(), keys.len());
+        finished.sort_by_key(|chunk| chunk.pos.x);
+
+        // Otherwise the comparison below proves nothing
+        let is_tree = |chunk: &Chunk, x: u8, y: u8, z: u8| {
+            matches!(chunk.get_block(LocalPos::new(x, y, z)), BlockId::LOG | BlockId::LEAVES)
+        };
+        let edge = CHUNK_SIZE - 1;
+        let straddles = finished.windows(2).any(|pair| {
+            (0..CHUNK_SIZE).any(|y| (0..CHUNK_SIZE).any(|z| is_tree(&pair[0], edge, y, z) && is_tree(&pair[1], 0, y, z)))
+        });
+        assert!(straddles, "no tree crosses a chunk border");
+
+        // Separately generated chunks agree with the ones generated together
+        for chunk in &finished {
+            let mut single = GenerationPipeline::default();
+            single.request(ChunkKey::new(chunk.pos, 0));
+            let alone = run_until_done(&mut single, &generator).pop().unwrap();
+            assert!(alone.blocks().iter().eq(chunk.blocks().iter()));
+        }
+    }
+
+    #[test]
+    fn test_saved_chunks_need_no_padding() {
+        let generator = heightmap(7);
+        let storage = RegionStorage::new(std::env::temp_dir().join(format!("aeternitas_pipeline_saved_{}", std::process::id())));
+        let key = ChunkKey::new(ChunkPos::new(0, -2, 0), 0);
+        let mut saved = Chunk::empty(key.pos, 0);
+        saved.set_block(LocalPos::new(1, 1, 1), BlockId::STONE);
+        storage.save_chunk(&saved).unwrap();
+
+        let mut pipeline = GenerationPipeline::default();
+        pipeline.request(key);
+        let mut finished = Vec::new();
+        for _ in 0..100_000 {
+            finished.extend(pipeline.advance(&generator, &storage, &empty_cache()));
+            // Nothing but the chunk itself ever enters the pipeline
+            assert!(pipeline.len() + pipeline.running_tasks() <= 1);
+            if finished.len() == 1 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let _ = std::fs::remove_dir_all(storage.root());
+
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].get_block(LocalPos::new(1, 1, 1)), BlockId::STONE);
+    }
+
+    #[test]
+    fn test_cancel_drops_tasks() {
+        let generator = heightmap(11);
+        let mut pipeline = GenerationPipeline::default();
+        let key = ChunkKey::new(ChunkPos::new(5, 0, 5), 0);
+
+        pipeline.request(key);
+        pipeline.advance(&generator, &empty_storage(), &empty_cache());
+        assert!(pipeline.running_tasks() > 0);
+        assert!(pipeline.running_tasks() <= pipeline.max_tasks);
+
+        pipeline.cancel(key);
+        pipeline.advance(&generator, &empty_storage(), &empty_cache());
+        assert!(pipeline.is_empty());
+    }
+}
@@ -0,0 +1,695 @@
+use crate::core::block::{BlockId, BlockRegistry, BlockState, Direction};
+use crate::core::fluid::{FluidId, FluidStack, FluidTank};
+use crate::core::inventory::{Inventory, ItemStack, SlotFilter};
+use crate::core::item::ItemId;
+use crate::core::position::{BlockPos, ChunkPos, LocalPos, CHUNK_SIZE};
+use crate::machine::processing::{Machine, MachineStatus};
+use crate::machine::recipe::MachineCategory;
+use crate::multiblock::registry::{Orientation, SlotRole};
+use crate::world::chunk::{BlockEntity, Chunk, CHUNK_VOLUME};
+use crate::world::palette::PalettedStorage;
+use bevy::prelude::*;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Chunks Per Region Axis (8x8x8 chunks per file)
+pub const REGION_SIZE: i32 = 8;
+const REGION_VOLUME: usize = (REGION_SIZE * REGION_SIZE * REGION_SIZE) as usize;
+
+const REGION_MAGIC: &[u8; 4] = b"AERG";
+/// Bump when the on-disk layout changes
+///
+/// 1: u16 block id palette, optional trailing facing section
+/// 2: u32 block state palette
+/// 3: machine block entities carry their inventories, tanks and progress
+/// 4: multiblock parts point at their controller, controllers store their pattern
+pub const REGION_VERSION: u16 = 4;
+const HEADER_LEN: usize = 4 + 2 + 2 + REGION_VOLUME * 8;
+
+/// Largest Machine Layout Read Back (corrupt counts must not allocate)
+const MAX_MACHINE_SLOTS: usize = 256;
+const MAX_MACHINE_TANKS: usize = 64;
+
+/// Region Position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl RegionPos {
+    pub fn from_chunk(pos: ChunkPos) -> Self {
+        Self {
+            x: pos.x.div_euclid(REGION_SIZE),
+            y: pos.y.div_euclid(REGION_SIZE),
+            z: pos.z.div_euclid(REGION_SIZE),
+        }
+    }
+
+    /// Slot Of A Chunk Inside Its Region
+    fn slot(pos: ChunkPos) -> usize {
+        let x = pos.x.rem_euclid(REGION_SIZE) as usize;
+        let y = pos.y.rem_euclid(REGION_SIZE) as usize;
+        let z = pos.z.rem_euclid(REGION_SIZE) as usize;
+        (y * REGION_SIZE as usize * REGION_SIZE as usize) + (z * REGION_SIZE as usize) + x
+    }
+
+    fn file_name(&self) -> String {
+        format!("r.{}.{}.{}.aer", self.x, self.y, self.z)
+    }
+}
+
+/// Region File Storage
+///
+/// Layout: magic, version, reserved, then an offset/length table with one
+/// entry per chunk slot, followed by the zlib-compressed chunk payloads.
+#[derive(Resource, Clone)]
+pub struct RegionStorage {
+    root: PathBuf,
+}
+
+impl Default for RegionStorage {
+    fn default() -> Self {
+        Self::new("saves/world/regions")
+    }
+}
+
+impl RegionStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn region_path(&self, region: RegionPos) -> PathBuf {
+        self.root.join(region.file_name())
+    }
+
+    /// Load A Saved Chunk (None if never saved)
+    pub fn load_chunk(&self, pos: ChunkPos) -> io::Result<Option<Chunk>> {
+        let path = self.region_path(RegionPos::from_chunk(pos));
+        let (version, mut entries) = match read_region(&path)? {
+            Some(region) => region,
+            None => return Ok(None),
+        };
+
+        match entries.remove(&RegionPos::slot(pos)) {
+            Some(compressed) => decompress_chunk(pos, &compressed, version).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Write A Chunk Into Its Region File
+    pub fn save_chunk(&self, chunk: &Chunk) -> io::Result<()> {
+        let path = self.region_path(RegionPos::from_chunk(chunk.pos));
+        let mut entries = read_current_region(&path)?.unwrap_or_default();
+        entries.insert(RegionPos::slot(chunk.pos), compress_chunk(chunk)?);
+
+        fs::create_dir_all(&self.root)?;
+        write_region(&path, &entries)
+    }
+
+    /// Drop A Saved Chunk (no-op if never saved)
+    pub fn remove_chunk(&self, pos: ChunkPos) -> io::Result<()> {
+        let path = self.region_path(RegionPos::from_chunk(pos));
+        let Some(mut entries) = read_current_region(&path)? else {
+            return Ok(());
+        };
+
+        if entries.remove(&RegionPos::slot(pos)).is_some() {
+            write_region(&path, &entries)?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Compressed Chunk Payloads By Region Slot
+type RegionEntries = HashMap<usize, Vec<u8>>;
+
+fn compress_chunk(chunk: &Chunk) -> io::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&encode_chunk(chunk))?;
+    encoder.finish()
+}
+
+fn decompress_chunk(pos: ChunkPos, compressed: &[u8], version: u16) -> io::Result<Chunk> {
+    let mut bytes = Vec::new();
+    ZlibDecoder::new(compressed).read_to_end(&mut bytes)?;
+    decode_chunk(pos, &bytes, version)
+}
+
+/// Region Entries Re-encoded In The Current Version (before rewriting a file)
+fn read_current_region(path: &Path) -> io::Result<Option<RegionEntries>> {
+    let Some((version, mut entries)) = read_region(path)? else {
+        return Ok(None);
+    };
+
+    if version != REGION_VERSION {
+        for compressed in entries.values_mut() {
+            // The position is not part of the payload
+            let chunk = decompress_chunk(ChunkPos::new(0, 0, 0), compressed, version)?;
+            *compressed = compress_chunk(&chunk)?;
+        }
+    }
+    Ok(Some(entries))
+}
+
+/// Raw Entries Of A Region File And Their Version
+fn read_region(path: &Path) -> io::Result<Option<(u16, RegionEntries)>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    if bytes.len() < HEADER_LEN || &bytes[0..4] != REGION_MAGIC {
+        return Err(invalid("not a region file"));
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version == 0 || version > REGION_VERSION {
+        return Err(invalid("unsupported region file version"));
+    }
+
+    let mut entries = HashMap::new();
+    for slot in 0..REGION_VOLUME {
+        let entry = 8 + slot * 8;
+        let offset = u32::from_le_bytes(bytes[entry..entry + 4].try_into().unwrap()) as usize;
+        let length = u32::from_le_bytes(bytes[entry + 4..entry + 8].try_into().unwrap()) as usize;
+        if length == 0 {
+            continue;
+        }
+        let payload = bytes
+            .get(offset..offset + length)
+            .ok_or_else(|| invalid("region entry out of bounds"))?;
+        entries.insert(slot, payload.to_vec());
+    }
+
+    Ok(Some((version, entries)))
+}
+
+fn write_region(path: &Path, entries: &RegionEntries) -> io::Result<()> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(REGION_MAGIC);
+    header.extend_from_slice(&REGION_VERSION.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+
+    let mut body = Vec::new();
+    for slot in 0..REGION_VOLUME {
+        match entries.get(&slot) {
+            Some(payload) => {
+                header.extend_from_slice(&((HEADER_LEN + body.len()) as u32).to_le_bytes());
+                header.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+                body.extend_from_slice(payload);
+            }
+            None => header.extend_from_slice(&[0; 8]),
+        }
+    }
+
+    // Write to a temp file first so a crash never leaves a torn region
+    let tmp = path.with_extension("aer.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(&header)?;
+    file.write_all(&body)?;
+    file.sync_all()?;
+    fs::rename(tmp, path)
+}
+
+/// Chunk Payload (uncompressed)
+fn encode_chunk(chunk: &Chunk) -> Vec<u8> {
+    let blocks = chunk.blocks();
+    let mut out = Vec::new();
+
+    out.push(chunk.depth);
+    out.extend_from_slice(&(blocks.palette().len() as u16).to_le_bytes());
+    for state in blocks.palette() {
+        out.extend_from_slice(&state.0.to_le_bytes());
+    }
+    out.push(blocks.bits_per_entry());
+    out.extend_from_slice(&(blocks.data().len() as u32).to_le_bytes());
+    for word in blocks.data() {
+        out.extend_from_slice(&word.to_le_bytes());
+    }
+
+    out.extend_from_slice(&(chunk.block_entities.len() as u32).to_le_bytes());
+    for (pos, entity) in &chunk.block_entities {
+        out.extend_from_slice(&[pos.x, pos.y, pos.z]);
+        match entity {
+            BlockEntity::Machine(machine) => {
+                out.push(0);
+                encode_machine(&mut out, machine);
+            }
+            BlockEntity::MultiblockPart { controller, role } => {
+                out.push(1);
+                for value in [controller.x, controller.y, controller.z] {
+                    out.extend_from_slice(&value.to_le_bytes());
+                }
+                // 0 for no role, otherwise the role index plus one
+                out.push(role.map_or(0, |role| role.index() + 1));
+            }
+            BlockEntity::Storage { .. } => out.push(2),
+            BlockEntity::MultiblockController { pattern, orientation } => {
+                out.push(3);
+                out.extend_from_slice(&(pattern.len() as u16).to_le_bytes());
+                out.extend_from_slice(pattern.as_bytes());
+                out.push(orientation.facing.index());
+                out.push(orientation.mirrored as u8);
+            }
+        }
+    }
+
+    out
+}
+
+fn decode_chunk(pos: ChunkPos, bytes: &[u8], version: u16) -> io::Result<Chunk> {
+    let mut reader = ByteReader { bytes, cursor: 0 };
+
+    let depth = reader.u8()?;
+    let palette_len = reader.u16()? as usize;
+    let mut palette = Vec::with_capacity(palette_len);
+    for _ in 0..palette_len {
+        let state = match version {
+            1 => BlockState::from(BlockId(reader.u16()?)),
+            _ => BlockState(reader.u32()?),
+        };
+        palette.push(state);
+    }
+    let bits = reader.u8()?;
+    let data_len = reader.u32()? as usize;
+    let mut data = Vec::with_capacity(data_len);
+    for _ in 0..data_len {
+        data.push(reader.u64()?);
+    }
+
+    let storage = PalettedStorage::from_parts(CHUNK_VOLUME, palette, bits, data)
+        .ok_or_else(|| invalid("corrupt chunk palette"))?;
+    let mut chunk = Chunk::from_blocks(pos, depth, storage);
+
+    let entity_count = reader.u32()?;
+    for _ in 0..entity_count {
+        let (x, y, z) = (reader.u8()?, reader.u8()?, reader.u8()?);
+        let entity = match reader.u8()? {
+            // Older machines were empty placeholders, drop them
+            0 if version < 3 => continue,
+            0 => BlockEntity::Machine(Box::new(decode_machine(&mut reader)?)),
+            // Older parts were empty placeholders too
+            1 if version < 4 => continue,
+            1 => {
+                let controller = BlockPos::new(reader.i32()?, reader.i32()?, reader.i32()?);
+                let role = match reader.u8()? {
+                    0 => None,
+                    index => Some(SlotRole::from_index(index - 1).ok_or_else(|| invalid("unknown slot role"))?),
+                };
+                BlockEntity::MultiblockPart { controller, role }
+            }
+            2 => BlockEntity::Storage {},
+            3 => {
+                let len = reader.u16()? as usize;
+                let pattern = String::from_utf8(reader.bytes(len)?.to_vec()).map_err(|_| invalid("corrupt pattern name"))?;
+                let facing = Direction::from_index(reader.u8()?).ok_or_else(|| invalid("unknown facing"))?;
+                let orientation = Orientation::new(facing, reader.u8()? != 0);
+                BlockEntity::MultiblockController { pattern, orientation }
+            }
+            _ => return Err(invalid("unknown block entity")),
+        };
+        chunk.block_entities.insert(LocalPos::new(x, y, z), entity);
+    }
+
+    // Version 1 may end with a facing section, which now lives in the state
+    // bits. Only built-in blocks existed back then, so the default registry
+    // decides which of them keep it.
+    if version == 1 && reader.cursor < reader.bytes.len() {
+        let registry = BlockRegistry::new();
+        for _ in 0..reader.u32()? {
+            let (x, y, z) = (reader.u8()?, reader.u8()?, reader.u8()?);
+            if x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE {
+                return Err(invalid("facing outside chunk"));
+            }
+            let facing = Direction::from_index(reader.u8()?).ok_or_else(|| invalid("unknown facing"))?;
+            let local = LocalPos::new(x, y, z);
+            let state = chunk.get_state(local);
+            let rotated = registry.with_facing(state, facing);
+            if rotated != state {
+                chunk.set_state(local, rotated);
+            }
+        }
+        // Migrated data is not an edit
+        chunk.mark_saved();
+        chunk.take_edited_faces();
+    }
+
+    Ok(chunk)
+}
+
+fn encode_machine(out: &mut Vec<u8>, machine: &Machine) {
+    out.push(machine.category.index());
+    for value in [machine.progress, machine.energy, machine.energy_capacity] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    for inventory in [&machine.input, &machine.output] {
+        out.extend_from_slice(&(inventory.slot_count() as u32).to_le_bytes());
+        for slot in 0..inventory.slot_count() {
+            match inventory.get(slot) {
+                Some(stack) => {
+                    out.push(1);
+                    for value in [stack.item.0, stack.count, stack.damage] {
+                        out.extend_from_slice(&value.to_le_bytes());
+                    }
+                }
+                None => out.push(0),
+            }
+        }
+    }
+
+    for tanks in [&machine.input_tanks, &machine.output_tanks] {
+        out.extend_from_slice(&(tanks.len() as u32).to_le_bytes());
+        for tank in tanks {
+            out.extend_from_slice(&tank.capacity.to_le_bytes());
+            match tank.contents {
+                Some(stack) => {
+                    out.push(1);
+                    out.extend_from_slice(&stack.fluid.0.to_le_bytes());
+                    out.extend_from_slice(&stack.amount.to_le_bytes());
+                }
+                None => out.push(0),
+            }
+        }
+    }
+}
+
+fn decode_machine(reader: &mut ByteReader) -> io::Result<Machine> {
+    let category = MachineCategory::from_index(reader.u8()?).ok_or_else(|| invalid("unknown machine category"))?;
+    let (progress, energy, energy_capacity) = (reader.u32()?, reader.u32()?, reader.u32()?);
+
+    let mut inventories = Vec::with_capacity(2);
+    for _ in 0..2 {
+        let slots = reader.u32()? as usize;
+        if slots > MAX_MACHINE_SLOTS {
+            return Err(invalid("too many machine slots"));
+        }
+        let mut inventory = Inventory::new(slots);
+        for slot in 0..inventory.slot_count() {
+            if reader.u8()? == 1 {
+                let (item, count, damage) = (reader.u32()?, reader.u32()?, reader.u32()?);
+                inventory.set(slot, Some(ItemStack { item: ItemId(item), count, damage }));
+            }
+        }
+        inventory.take_changed();
+        inventories.push(inventory);
+    }
+
+    let mut tank_lists = Vec::with_capacity(2);
+    for _ in 0..2 {
+        let count = reader.u32()? as usize;
+        if count > MAX_MACHINE_TANKS {
+            return Err(invalid("too many machine tanks"));
+        }
+        let mut tanks = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut tank = FluidTank::new(reader.u32()?);
+            if reader.u8()? == 1 {
+                tank.contents = Some(FluidStack::new(FluidId(reader.u16()?), reader.u32()?));
+            }
+            tanks.push(tank);
+        }
+        tank_lists.push(tanks);
+    }
+
+    let output_tanks = tank_lists.pop().unwrap();
+    let input_tanks = tank_lists.pop().unwrap();
+    let mut output = inventories.pop().unwrap();
+    let input = inventories.pop().unwrap();
+    for slot in 0..output.slot_count() {
+        output.set_filter(slot, SlotFilter::Output);
+    }
+
+    Ok(Machine {
+        category,
+        input,
+        output,
+        input_tanks,
+        output_tanks,
+        // Found again from the inputs on the next tick
+        recipe: None,
+        progress,
+        energy,
+        energy_capacity,
+        status: MachineStatus::Idle,
+    })
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+}
+
+impl ByteReader<'_> {
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let slice = self
+            .bytes
+            .get(self.cursor..self.cursor + N)
+            .ok_or_else(|| invalid("truncated chunk payload"))?;
+        self.cursor += N;
+        Ok(slice.try_into().unwrap())
+    }
+
+    fn bytes(&mut self, len: usize) -> io::Result<&[u8]> {
+        let slice = self
+            .bytes
+            .get(self.cursor..self.cursor + len)
+            .ok_or_else(|| invalid("truncated chunk payload"))?;
+        self.cursor += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+}
+
+/// Autosave Edited Chunks
+pub fn save_modified_chunks(
+    time: Res<Time>,
+    mut timer: Local<Option<Timer>>,
+    region_storage: Res<RegionStorage>,
+    mut query: Query<&mut Chunk>,
+) {
+    let timer = timer.get_or_insert_with(|| Timer::from_seconds(5.0, TimerMode::Repeating));
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for mut chunk in query.iter_mut() {
+        // Failed writes keep the chunk flagged, so the next autosave retries
+        if chunk.needs_save() && save_or_warn(&region_storage, &chunk).is_ok() {
+            // Saving must not trigger a remesh
+            chunk.bypass_change_detection().mark_saved();
+        }
+    }
+}
+
+/// Save Everything On Exit
+pub fn save_chunks_on_exit(
+    mut exit_events: MessageReader<AppExit>,
+    region_storage: Res<RegionStorage>,
+    query: Query<&Chunk>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+
+    for chunk in query.iter().filter(|chunk| chunk.needs_save()) {
+        // Nothing left to retry with, the warning is all we can do
+        let _ = save_or_warn(&region_storage, chunk);
+    }
+}
+
+/// Save A Chunk, Logging Failures (callers decide whether to keep the edits around)
+pub fn save_or_warn(region_storage: &RegionStorage, chunk: &Chunk) -> io::Result<()> {
+    let result = region_storage.save_chunk(chunk);
+    if let Err(err) = &result {
+        warn!("Failed to save chunk at {:?}: {}", chunk.pos, err);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine::processing::MachineRegistry;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn temp_storage(name: &str) -> RegionStorage {
+        let root = std::env::temp_dir().join(format!("aeternitas_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        RegionStorage::new(root)
+    }
+
+    #[test]
+    fn test_chunk_roundtrip() {
+        let storage = temp_storage("roundtrip");
+        let pos = ChunkPos::new(3, -2, 9);
+
+        let mut chunk = Chunk::empty(pos, 0);
+        chunk.set_block(LocalPos::new(1, 2, 3), BlockId::STONE);
+        chunk.set_block(LocalPos::new(31, 31, 31), BlockId::GRASS);
+        chunk.block_entities.insert(LocalPos::new(1, 2, 3), BlockEntity::Storage {});
+        let registry = BlockRegistry::new();
+        let furnace = registry.with_facing(BlockId::FURNACE.into(), Direction::East);
+        chunk.set_state(LocalPos::new(30, 31, 31), furnace);
+        let mut machine = Machine::new(MachineRegistry::default().get(BlockId::FURNACE).unwrap());
+        machine.input.set(0, Some(ItemStack::new(ItemId::from_block(BlockId::LOG), 3)));
+        machine.output.set(1, Some(ItemStack::new(ItemId::CHARCOAL, 2)));
+        machine.input.take_changed();
+        machine.output.take_changed();
+        machine.progress = 40;
+        machine.energy = 900;
+        chunk.block_entities.insert(LocalPos::new(30, 31, 31), BlockEntity::Machine(Box::new(machine.clone())));
+        let part = BlockEntity::MultiblockPart { controller: BlockPos::new(-4, 70, 300), role: Some(SlotRole::EnergyPort) };
+        let controller = BlockEntity::MultiblockController {
+            pattern: "blast_furnace".to_string(),
+            orientation: Orientation::new(Direction::West, true),
+        };
+        chunk.block_entities.insert(LocalPos::new(0, 0, 1), part.clone());
+        chunk.block_entities.insert(LocalPos::new(0, 0, 2), controller.clone());
+        storage.save_chunk(&chunk).unwrap();
+
+        // Second chunk in the same region must not clobber the first
+        let neighbor = ChunkPos::new(4, -2, 9);
+        storage.save_chunk(&Chunk::empty(neighbor, 0)).unwrap();
+
+        let loaded = storage.load_chunk(pos).unwrap().unwrap();
+        assert_eq!(loaded.get_block(LocalPos::new(1, 2, 3)), BlockId::STONE);
+        assert_eq!(loaded.get_block(LocalPos::new(31, 31, 31)), BlockId::GRASS);
+        assert_eq!(loaded.get_block(LocalPos::new(0, 0, 0)), BlockId::AIR);
+        assert_eq!(loaded.block_entities.len(), 4);
+        assert_eq!(loaded.block_entities.get(&LocalPos::new(0, 0, 1)), Some(&part));
+        assert_eq!(loaded.block_entities.get(&LocalPos::new(0, 0, 2)), Some(&controller));
+        assert_eq!(loaded.get_state(LocalPos::new(30, 31, 31)), furnace);
+        let Some(BlockEntity::Machine(loaded_machine)) = loaded.block_entities.get(&LocalPos::new(30, 31, 31)) else {
+            panic!("machine was not loaded");
+        };
+        assert_eq!(**loaded_machine, machine);
+        assert!(!loaded.needs_save());
+
+        assert!(storage.load_chunk(neighbor).unwrap().is_some());
+        assert!(storage.load_chunk(ChunkPos::new(5, -2, 9)).unwrap().is_none());
+
+        let _ = fs::remove_dir_all(storage.root());
+    }
+
+    #[test]
+    fn test_v1_facing_migrates_into_state() {
+        let pos = ChunkPos::new(0, 0, 0);
+        let furnace_pos = LocalPos::new(4, 5, 6);
+        let stone_pos = LocalPos::new(31, 0, 0);
+        let mut chunk = Chunk::empty(pos, 0);
+        chunk.set_block(furnace_pos, BlockId::FURNACE);
+        chunk.set_block(stone_pos, BlockId::STONE);
+
+        // Hand-built version 1 payload: u16 block ids, then the facing section
+        let blocks = chunk.blocks();
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&(blocks.palette().len() as u16).to_le_bytes());
+        for state in blocks.palette() {
+            bytes.extend_from_slice(&state.block().0.to_le_bytes());
+        }
+        bytes.push(blocks.bits_per_entry());
+        bytes.extend_from_slice(&(blocks.data().len() as u32).to_le_bytes());
+        for word in blocks.data() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        for local in [furnace_pos, stone_pos] {
+            bytes.extend_from_slice(&[local.x, local.y, local.z, Direction::East.index()]);
+        }
+
+        let registry = BlockRegistry::new();
+        let migrated = decode_chunk(pos, &bytes, 1).unwrap();
+        assert_eq!(registry.facing(migrated.get_state(furnace_pos)), Some(Direction::East));
+        // Stone has no facing, its state stays plain
+        assert_eq!(migrated.get_state(stone_pos), BlockState::from(BlockId::STONE));
+        assert!(!migrated.needs_save());
+
+        let reloaded = decode_chunk(pos, &encode_chunk(&migrated), REGION_VERSION).unwrap();
+        assert_eq!(reloaded.get_state(furnace_pos), migrated.get_state(furnace_pos));
+        assert_eq!(reloaded.get_state(stone_pos), BlockState::from(BlockId::STONE));
+    }
+
+    #[test]
+    fn test_oversized_machine_is_rejected() {
+        let machine = Machine::new(MachineRegistry::default().get(BlockId::FURNACE).unwrap());
+        let mut bytes = Vec::new();
+        encode_machine(&mut bytes, &machine);
+        assert!(decode_machine(&mut ByteReader { bytes: &bytes, cursor: 0 }).is_ok());
+
+        // Input slot count, right after the category, progress and energy
+        bytes[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode_machine(&mut ByteReader { bytes: &bytes, cursor: 0 }).is_err());
+    }
+
+    #[test]
+    fn test_failed_autosave_keeps_edits() {
+        let blocker = std::env::temp_dir().join(format!("aeternitas_blocker_{}", std::process::id()));
+        fs::write(&blocker, b"not a directory").unwrap();
+
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(std::time::Duration::from_secs(5));
+        world.insert_resource(time);
+        // Regions cannot be created below a plain file
+        world.insert_resource(RegionStorage::new(blocker.join("regions")));
+        let mut chunk = Chunk::empty(ChunkPos::new(0, 0, 0), 0);
+        chunk.set_block(LocalPos::new(1, 1, 1), BlockId::STONE);
+        let entity = world.spawn(chunk).id();
+
+        world.run_system_once(save_modified_chunks).unwrap();
+        assert!(world.entity(entity).get::<Chunk>().unwrap().needs_save());
+
+        let storage = temp_storage("autosave");
+        world.insert_resource(storage.clone());
+        world.run_system_once(save_modified_chunks).unwrap();
+        assert!(!world.entity(entity).get::<Chunk>().unwrap().needs_save());
+
+        let _ = fs::remove_file(&blocker);
+        let _ = fs::remove_dir_all(storage.root());
+    }
+
+    #[test]
+    fn test_region_grouping() {
+        assert_eq!(RegionPos::from_chunk(ChunkPos::new(-1, 0, 7)), RegionPos { x: -1, y: 0, z: 0 });
+        assert_ne!(
+            RegionPos::slot(ChunkPos::new(0, 0, 1)),
+            RegionPos::slot(ChunkPos::new(1, 0, 0))
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::core::block::{BlockId, BlockState};
+use crate::core::position::BlockPos;
+use crate::world::chunk::{BlockEntity, Chunk};
+use crate::world::chunk_manager::ChunkManager;
+use crate::world::events::{BlockChangeCause, BlockChanged};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// Block Access By Global Position
+///
+/// Resolves the chunk through the `ChunkManager`, so callers never touch
+/// chunk entities or local positions. Positions wrap horizontally through
+/// `BlockPos`. Blocks in unloaded chunks, or in chunks only loaded at a
+/// coarser LOD, read as `None` and cannot be written.
+///
+/// Writes go through `Chunk::set_block`, which flags the chunk and the
+/// border faces it touched, so the chunk and its neighbors get remeshed.
+/// Every actual change is announced with a `BlockChanged` message.
+#[derive(SystemParam)]
+pub struct VoxelWorld<'w, 's> {
+    chunk_manager: Res<'w, ChunkManager>,
+    chunks: Query<'w, 's, &'static mut Chunk>,
+    changes: MessageWriter<'w, BlockChanged>,
+}
+
+impl VoxelWorld<'_, '_> {
+    /// Full Resolution Chunk Holding A Block
+    pub fn chunk(&self, pos: BlockPos) -> Option<&Chunk> {
+        let entity = self.chunk_manager.get_chunk_entity(pos.chunk_pos())?;
+        self.chunks.get(entity).ok().filter(|chunk| chunk.depth == 0)
+    }
+
+    pub fn is_loaded(&self, pos: BlockPos) -> bool {
+        self.chunk(pos).is_some()
+    }
+
+    pub fn get_block(&self, pos: BlockPos) -> Option<BlockId> {
+        self.get_state(pos).map(BlockState::block)
+    }
+
+    pub fn get_state(&self, pos: BlockPos) -> Option<BlockState> {
+        self.chunk(pos).map(|chunk| chunk.get_state(pos.local_pos()))
+    }
+
+    /// Replace A Block With Its Default State, Returning The Previous Block (None if not loaded)
+    pub fn set_block(&mut self, pos: BlockPos, block: BlockId, cause: BlockChangeCause) -> Option<BlockId> {
+        self.set_state(pos, block.into(), cause).map(BlockState::block)
+    }
+
+    /// Replace A Block State, Returning The Previous One (None if not loaded)
+    pub fn set_state(&mut self, pos: BlockPos, state: BlockState, cause: BlockChangeCause) -> Option<BlockState> {
+        let entity = self.chunk_manager.get_chunk_entity(pos.chunk_pos())?;
+        let mut chunk = self.chunks.get_mut(entity).ok().filter(|chunk| chunk.depth == 0)?;
+
+        let local = pos.local_pos();
+        let old = chunk.get_state(local);
+        if old != state {
+            chunk.set_state(local, state);
+            self.changes.write(BlockChanged { pos, old, new: state, cause });
+        }
+        Some(old)
+    }
+
+    pub fn block_entity(&self, pos: BlockPos) -> Option<&BlockEntity> {
+        self.chunk(pos)?.block_entities.get(&pos.local_pos())
+    }
+
+    /// Replace Or Remove A Block Entity, Returning The Previous One
+    ///
+    /// Block entities never change how a chunk looks, so this skips change
+    /// detection and only flags the chunk for saving. Does nothing when the
+    /// chunk is not loaded.
+    pub fn set_block_entity(&mut self, pos: BlockPos, entity: Option<BlockEntity>) -> Option<BlockEntity> {
+        let chunk_entity = self.chunk_manager.get_chunk_entity(pos.chunk_pos())?;
+        let mut chunk = self.chunks.get_mut(chunk_entity).ok().filter(|chunk| chunk.depth == 0)?;
+
+        let chunk = chunk.bypass_change_detection();
+        chunk.mark_needs_save();
+        match entity {
+            Some(entity) => chunk.block_entities.insert(pos.local_pos(), entity),
+            None => chunk.block_entities.remove(&pos.local_pos()),
+        }
+    }
+
+    /// Every Block In The Box `min..min + size`, Wrapping Horizontally
+    pub fn iter_region(&self, min: BlockPos, size: IVec3) -> impl Iterator<Item = (BlockPos, Option<BlockId>)> + '_ {
+        let size = size.max(IVec3::ZERO);
+        (0..size.y).flat_map(move |y| {
+            (0..size.z).flat_map(move |z| {
+                (0..size.x).map(move |x| {
+                    let pos = min + IVec3::new(x, y, z);
+                    (pos, self.get_block(pos))
+                })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::{ChunkPos, LocalPos, CHUNK_SIZE_I32};
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn world_with_chunks(chunks: &[(ChunkPos, u8)]) -> World {
+        let mut world = World::new();
+        world.init_resource::<Messages<BlockChanged>>();
+        let mut manager = ChunkManager::default();
+        for (pos, depth) in chunks {
+            let entity = world.spawn(Chunk::empty(*pos, *depth)).id();
+            manager.register_chunk(*pos, entity);
+        }
+        world.insert_resource(manager);
+        world
+    }
+
+    #[test]
+    fn test_set_and_get_across_chunks() {
+        let mut world = world_with_chunks(&[(ChunkPos::new(0, 0, 0), 0), (ChunkPos::new(-1, 0, 0), 0)]);
+
+        world
+            .run_system_once(|mut voxels: VoxelWorld| {
+                assert_eq!(voxels.set_block(BlockPos::new(0, 3, 0), BlockId::STONE, BlockChangeCause::Player), Some(BlockId::AIR));
+                assert_eq!(voxels.set_block(BlockPos::new(-1, 3, 0), BlockId::DIRT, BlockChangeCause::Player), Some(BlockId::AIR));
+                // Nothing loaded above
+                assert_eq!(voxels.set_block(BlockPos::new(0, CHUNK_SIZE_I32, 0), BlockId::STONE, BlockChangeCause::Player), None);
+            })
+            .unwrap();
+
+        world
+            .run_system_once(|voxels: VoxelWorld| {
+                assert_eq!(voxels.get_block(BlockPos::new(0, 3, 0)), Some(BlockId::STONE));
+                assert_eq!(voxels.get_block(BlockPos::new(-1, 3, 0)), Some(BlockId::DIRT));
+                assert_eq!(voxels.get_block(BlockPos::new(0, CHUNK_SIZE_I32, 0)), None);
+
+                let solid = voxels
+                    .iter_region(BlockPos::new(-2, 3, 0), IVec3::new(4, 1, 1))
+                    .filter(|(_, block)| block.is_some_and(|block| block != BlockId::AIR))
+                    .count();
+                assert_eq!(solid, 2);
+            })
+            .unwrap();
+
+        // Both edits sit on the shared X border and the -Z border
+        let mut chunks = world.query::<&mut Chunk>();
+        for mut chunk in chunks.iter_mut(&mut world) {
+            assert!(chunk.dirty);
+            let expected = if chunk.pos.x == 0 { 0b10_0010 } else { 0b10_0001 };
+            assert_eq!(chunk.take_edited_faces(), expected);
+        }
+    }
+
+    #[test]
+    fn test_coarse_chunks_are_read_only() {
+        let mut world = world_with_chunks(&[(ChunkPos::new(0, 0, 0), 1)]);
+
+        world
+            .run_system_once(|mut voxels: VoxelWorld| {
+                assert_eq!(voxels.get_block(BlockPos::new(1, 1, 1)), None);
+                assert_eq!(voxels.set_block(BlockPos::new(1, 1, 1), BlockId::STONE, BlockChangeCause::Player), None);
+            })
+            .unwrap();
+
+        let mut chunks = world.query::<&Chunk>();
+        let chunk = chunks.single(&world).unwrap();
+        assert_eq!(chunk.get_block(LocalPos::new(0, 0, 0)), BlockId::AIR);
+    }
+}